@@ -0,0 +1,148 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use rand::{thread_rng, Rng};
+
+use super::{measure, ROUNDS};
+use crate::data_structure::fibonacci_heap::FibonacciHeap;
+use crate::data_structure::leftist_heap::LeftistHeap;
+use crate::data_structure::pairing_heap::PairingHeap;
+use crate::data_structure::priority_queue::{MinFirst, PriorityQueue};
+use crate::graph::dijkstra::{dijkstra, dijkstra_fibonacci, dijkstra_indexed};
+
+// 各种小根堆的公共操作
+trait MinHeap {
+    fn create() -> Self;
+    fn push(&mut self, x: i32);
+    fn pop(&mut self) -> Option<i32>;
+}
+
+impl MinHeap for BinaryHeap<Reverse<i32>> {
+    fn create() -> Self {
+        BinaryHeap::new()
+    }
+    fn push(&mut self, x: i32) {
+        BinaryHeap::push(self, Reverse(x));
+    }
+    fn pop(&mut self) -> Option<i32> {
+        BinaryHeap::pop(self).map(|x| x.0)
+    }
+}
+
+macro_rules! impl_min_heap {
+    ($($t:ident),*) => {
+        $(impl MinHeap for $t<i32, MinFirst> {
+            fn create() -> Self {
+                $t::new_min(None)
+            }
+            fn push(&mut self, x: i32) {
+                $t::push(self, x);
+            }
+            fn pop(&mut self) -> Option<i32> {
+                $t::pop(self)
+            }
+        })*
+    };
+}
+
+impl_min_heap!(PriorityQueue, PairingHeap, LeftistHeap, FibonacciHeap);
+
+// 先全部插入再全部弹出
+fn push_all<H: MinHeap>(nums: &[i32]) -> Vec<i32> {
+    let mut heap = H::create();
+    for &x in nums {
+        heap.push(x);
+    }
+    std::iter::from_fn(|| heap.pop()).collect()
+}
+
+// 每插入三个元素弹出一个，最后清空
+fn mixed<H: MinHeap>(nums: &[i32]) -> Vec<i32> {
+    let mut heap = H::create();
+    let mut res = Vec::with_capacity(nums.len());
+    for (i, &x) in nums.iter().enumerate() {
+        heap.push(x);
+        if i % 3 == 2 {
+            res.extend(heap.pop());
+        }
+    }
+    res.extend(std::iter::from_fn(|| heap.pop()));
+    res
+}
+
+type Workload = fn(&[i32]) -> Vec<i32>;
+
+fn print_row(name: &str, cases: &[(Workload, &[i32], &[i32])]) {
+    print!("{:<22}", name);
+    for &(f, input, expect) in cases {
+        assert_eq!(f(input), expect, "{name} gives a wrong result");
+        let time = measure(|| {
+            f(input);
+        });
+        print!("{:>12.3}", time.as_secs_f64() * 1000.0);
+    }
+    println!();
+}
+
+fn heap_row<H: MinHeap>(name: &str, nums: &[i32], expect: &[Vec<i32>; 2]) {
+    print_row(
+        name,
+        &[
+            (push_all::<H>, nums, &expect[0]),
+            (mixed::<H>, nums, &expect[1]),
+        ],
+    );
+}
+
+type Graph = Vec<Vec<(usize, i32)>>;
+type ShortestPath = fn(&Graph, usize, usize) -> Vec<i32>;
+
+// 随机图，n 个顶点、m 条边
+fn random_graph(n: usize, m: usize) -> Graph {
+    let mut rng = thread_rng();
+    let mut map = vec![vec![]; n];
+    for _ in 0..m {
+        let u = rng.gen_range(0..n);
+        let v = rng.gen_range(0..n);
+        map[u].push((v, rng.gen_range(0..1000)));
+    }
+    map
+}
+
+// 比较各种堆的插入、弹出以及在 dijkstra 中的耗时
+pub fn run(n: usize) {
+    let mut rng = thread_rng();
+    let nums = (0..n).map(|_| rng.gen()).collect::<Vec<i32>>();
+    let expect = [
+        push_all::<BinaryHeap<Reverse<i32>>>(&nums),
+        mixed::<BinaryHeap<Reverse<i32>>>(&nums),
+    ];
+
+    println!("n = {n}, best of {ROUNDS} rounds, ms");
+    println!("{:<22}{:>12}{:>12}", "", "push+pop", "mixed");
+    heap_row::<BinaryHeap<Reverse<i32>>>("std BinaryHeap", &nums, &expect);
+    heap_row::<PriorityQueue<i32, MinFirst>>("PriorityQueue", &nums, &expect);
+    heap_row::<PairingHeap<i32, MinFirst>>("PairingHeap", &nums, &expect);
+    heap_row::<LeftistHeap<i32, MinFirst>>("LeftistHeap", &nums, &expect);
+    heap_row::<FibonacciHeap<i32, MinFirst>>("FibonacciHeap", &nums, &expect);
+
+    let vertices = (n / 4).max(1);
+    let map = random_graph(vertices, n);
+    println!();
+    println!("dijkstra, {vertices} vertices, {n} edges, ms");
+    let dis = dijkstra(&map, 0, vertices);
+    let algs: [(&str, ShortestPath); 3] = [
+        ("std BinaryHeap (lazy)", dijkstra),
+        ("IndexedPriorityQueue", |map, s, n| {
+            dijkstra_indexed(map, s, n)
+        }),
+        ("FibonacciHeap", |map, s, n| dijkstra_fibonacci(map, s, n)),
+    ];
+    for (name, f) in algs {
+        assert_eq!(f(&map, 0, vertices), dis, "{name} gives a wrong result");
+        let time = measure(|| {
+            f(&map, 0, vertices);
+        });
+        println!("{:<22}{:>12.3}", name, time.as_secs_f64() * 1000.0);
+    }
+}
@@ -0,0 +1,31 @@
+use std::time::{Duration, Instant};
+
+pub mod heap;
+pub mod sort;
+
+const ROUNDS: usize = 3;
+
+// 多轮运行取最短耗时
+fn measure(f: impl Fn()) -> Duration {
+    (0..ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+// 每轮先用 setup 准备输入，只统计 f 的耗时
+fn measure_with<S>(setup: impl Fn() -> S, f: impl Fn(&mut S)) -> Duration {
+    (0..ROUNDS)
+        .map(|_| {
+            let mut input = setup();
+            let start = Instant::now();
+            f(&mut input);
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
@@ -0,0 +1,30 @@
+use super::{measure_with, ROUNDS};
+use crate::sort::verify::{algorithms, check, Pattern};
+
+// 对每种输入模式运行所有排序算法，校验结果并输出耗时（取多轮中的最小值）
+pub fn run(n: usize) {
+    let algs = algorithms();
+    println!("n = {n}, best of {ROUNDS} rounds, ms");
+    print!("{:<22}", "");
+    for pattern in Pattern::ALL {
+        print!("{:>12}", pattern.name());
+    }
+    println!();
+
+    let inputs = Pattern::ALL
+        .iter()
+        .map(|p| p.generate(n))
+        .collect::<Vec<_>>();
+    for alg in &algs {
+        print!("{:<22}", alg.name);
+        for input in &inputs {
+            if let Err(e) = check(alg, input) {
+                println!();
+                panic!("{e}");
+            }
+            let time = measure_with(|| input.clone(), |nums| (alg.sort)(nums));
+            print!("{:>12.3}", time.as_secs_f64() * 1000.0);
+        }
+        println!();
+    }
+}
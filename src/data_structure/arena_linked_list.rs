@@ -0,0 +1,453 @@
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static LIST_ID: AtomicUsize = AtomicUsize::new(0);
+
+// 节点句柄，节点被删除后 generation 不再匹配，旧句柄自动失效
+// list 记录发出句柄的链表，其他链表不接受
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Index {
+    slot: usize,
+    generation: u64,
+    list: usize,
+}
+
+struct Slot<T> {
+    // None 表示空闲，空闲时 next 指向下一个空闲位置
+    val: Option<T>,
+    generation: u64,
+    next: Option<usize>,
+    pre: Option<usize>,
+}
+
+// 节点存放在 Vec 中的双向链表，删除的位置放入空闲链表复用
+pub struct ArenaLinkedList<T> {
+    slots: Vec<Slot<T>>,
+    head: Option<usize>,
+    tail: Option<usize>,
+    free: Option<usize>,
+    len: usize,
+    id: usize,
+}
+
+impl<T> ArenaLinkedList<T> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        ArenaLinkedList {
+            slots: Vec::with_capacity(capacity),
+            head: None,
+            tail: None,
+            free: None,
+            len: 0,
+            id: LIST_ID.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn index(&self, slot: usize) -> Index {
+        Index {
+            slot,
+            generation: self.slots[slot].generation,
+            list: self.id,
+        }
+    }
+
+    // 句柄有效时返回对应的位置
+    fn slot(&self, idx: Index) -> Option<usize> {
+        if idx.list != self.id {
+            return None;
+        }
+        let slot = self.slots.get(idx.slot)?;
+        (slot.val.is_some() && slot.generation == idx.generation).then_some(idx.slot)
+    }
+
+    pub fn contains(&self, idx: Index) -> bool {
+        self.slot(idx).is_some()
+    }
+
+    pub fn get(&self, idx: Index) -> Option<&T> {
+        self.slot(idx).and_then(|i| self.slots[i].val.as_ref())
+    }
+
+    pub fn get_mut(&mut self, idx: Index) -> Option<&mut T> {
+        self.slot(idx).and_then(|i| self.slots[i].val.as_mut())
+    }
+
+    fn alloc(&mut self, val: T) -> usize {
+        match self.free {
+            Some(i) => {
+                self.free = self.slots[i].next;
+                self.slots[i].val = Some(val);
+                i
+            }
+            None => {
+                self.slots.push(Slot {
+                    val: Some(val),
+                    generation: 0,
+                    next: None,
+                    pre: None,
+                });
+                self.slots.len() - 1
+            }
+        }
+    }
+
+    // 把 slot 接到 pre 与 next 之间
+    fn link(&mut self, slot: usize, pre: Option<usize>, next: Option<usize>) {
+        self.slots[slot].pre = pre;
+        self.slots[slot].next = next;
+        match pre {
+            Some(p) => self.slots[p].next = Some(slot),
+            None => self.head = Some(slot),
+        }
+        match next {
+            Some(n) => self.slots[n].pre = Some(slot),
+            None => self.tail = Some(slot),
+        }
+        self.len += 1;
+    }
+
+    fn unlink(&mut self, slot: usize) {
+        let (pre, next) = (self.slots[slot].pre, self.slots[slot].next);
+        match pre {
+            Some(p) => self.slots[p].next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(n) => self.slots[n].pre = pre,
+            None => self.tail = pre,
+        }
+        self.len -= 1;
+    }
+
+    fn insert_between(&mut self, pre: Option<usize>, next: Option<usize>, val: T) -> Index {
+        let slot = self.alloc(val);
+        self.link(slot, pre, next);
+        self.index(slot)
+    }
+
+    pub fn push_front(&mut self, val: T) -> Index {
+        self.insert_between(None, self.head, val)
+    }
+
+    pub fn push_back(&mut self, val: T) -> Index {
+        self.insert_between(self.tail, None, val)
+    }
+
+    // idx 失效时返回 None
+    pub fn insert_after(&mut self, idx: Index, val: T) -> Option<Index> {
+        let slot = self.slot(idx)?;
+        Some(self.insert_between(Some(slot), self.slots[slot].next, val))
+    }
+
+    pub fn insert_before(&mut self, idx: Index, val: T) -> Option<Index> {
+        let slot = self.slot(idx)?;
+        Some(self.insert_between(self.slots[slot].pre, Some(slot), val))
+    }
+
+    pub fn remove(&mut self, idx: Index) -> Option<T> {
+        let slot = self.slot(idx)?;
+        self.unlink(slot);
+        let s = &mut self.slots[slot];
+        s.generation += 1;
+        s.pre = None;
+        s.next = self.free;
+        self.free = Some(slot);
+        s.val.take()
+    }
+
+    pub fn front(&self) -> Option<Index> {
+        self.head.map(|i| self.index(i))
+    }
+
+    pub fn back(&self) -> Option<Index> {
+        self.tail.map(|i| self.index(i))
+    }
+
+    pub fn next(&self, idx: Index) -> Option<Index> {
+        let slot = self.slot(idx)?;
+        self.slots[slot].next.map(|i| self.index(i))
+    }
+
+    pub fn prev(&self, idx: Index) -> Option<Index> {
+        let slot = self.slot(idx)?;
+        self.slots[slot].pre.map(|i| self.index(i))
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.front().and_then(|idx| self.remove(idx))
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.back().and_then(|idx| self.remove(idx))
+    }
+
+    // 移动节点不会改变句柄
+    pub fn move_to_front(&mut self, idx: Index) -> bool {
+        let Some(slot) = self.slot(idx) else {
+            return false;
+        };
+        self.unlink(slot);
+        self.link(slot, None, self.head);
+        true
+    }
+
+    pub fn move_to_back(&mut self, idx: Index) -> bool {
+        let Some(slot) = self.slot(idx) else {
+            return false;
+        };
+        self.unlink(slot);
+        self.link(slot, self.tail, None);
+        true
+    }
+
+    // 清空后所有旧句柄失效，已分配的空间保留
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            slots: self.slots.as_mut_ptr(),
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    // 按链表顺序返回所有句柄
+    pub fn indices(&self) -> impl Iterator<Item = Index> + '_ {
+        let mut iter = self.iter();
+        std::iter::from_fn(move || iter.next_slot()).map(|i| self.index(i))
+    }
+}
+
+impl<T> Default for ArenaLinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Extend<T> for ArenaLinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push_back(item);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for ArenaLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+pub struct Iter<'a, T> {
+    list: &'a ArenaLinkedList<T>,
+    head: Option<usize>,
+    tail: Option<usize>,
+    len: usize,
+}
+
+impl<T> Iter<'_, T> {
+    fn next_slot(&mut self) -> Option<usize> {
+        if self.len == 0 {
+            return None;
+        }
+        let slot = self.head?;
+        self.head = self.list.slots[slot].next;
+        self.len -= 1;
+        Some(slot)
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let list = self.list;
+        self.next_slot().and_then(|i| list.slots[i].val.as_ref())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let slot = self.tail?;
+        self.tail = self.list.slots[slot].pre;
+        self.len -= 1;
+        self.list.slots[slot].val.as_ref()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+// 每个位置至多返回一次，可以同时持有多个可变引用
+pub struct IterMut<'a, T> {
+    slots: *mut Slot<T>,
+    head: Option<usize>,
+    tail: Option<usize>,
+    len: usize,
+    marker: PhantomData<&'a mut Slot<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let slot = unsafe { &mut *self.slots.add(self.head?) };
+        self.head = slot.next;
+        self.len -= 1;
+        slot.val.as_mut()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let slot = unsafe { &mut *self.slots.add(self.tail?) };
+        self.tail = slot.pre;
+        self.len -= 1;
+        slot.val.as_mut()
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{thread_rng, Rng};
+    use std::collections::VecDeque;
+
+    #[test]
+    fn test1() {
+        let mut list = ArenaLinkedList::new();
+        let b = list.push_back("b");
+        let a = list.push_front("a");
+        let c = list.insert_after(b, "c").unwrap();
+        list.insert_before(b, "ab");
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            ["a", "ab", "b", "c"]
+        );
+        assert_eq!(list.iter().rev().count(), 4);
+        assert_eq!(list.next(a).and_then(|x| list.next(x)), Some(b));
+        assert_eq!(list.prev(a), None);
+        assert_eq!(list.back(), Some(c));
+
+        assert_eq!(list.remove(b), Some("b"));
+        // 旧句柄失效，即使位置被复用
+        let d = list.push_back("d");
+        assert_eq!(list.get(b), None);
+        assert_eq!(list.remove(b), None);
+        assert_eq!(list.insert_after(b, "x"), None);
+        assert_eq!(list.get(d), Some(&"d"));
+
+        list.move_to_front(c);
+        list.move_to_back(a);
+        *list.get_mut(d).unwrap() = "D";
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            ["c", "ab", "D", "a"]
+        );
+        assert_eq!(list.indices().collect::<Vec<Index>>()[0], c);
+        for x in list.iter_mut().rev().take(2) {
+            *x = "z";
+        }
+        assert_eq!(list.pop_back(), Some("z"));
+        assert_eq!(list.pop_front(), Some("c"));
+        list.clear();
+        assert!(list.is_empty());
+        assert!(!list.contains(d));
+    }
+
+    #[test]
+    fn test_foreign_index() {
+        let mut a = ArenaLinkedList::new();
+        let mut b = ArenaLinkedList::new();
+        let x = a.push_back(1);
+        let y = b.push_back(2);
+        // 位置和版本号都相同，但属于不同的链表
+        assert_eq!(b.get(x), None);
+        assert_eq!(b.remove(x), None);
+        assert_eq!(b.insert_after(x, 3), None);
+        assert!(!b.move_to_front(x));
+        assert_eq!(a.get(y), None);
+        assert_eq!((a.len(), b.len()), (1, 1));
+        assert_eq!(a.remove(x), Some(1));
+        assert_eq!(b.remove(y), Some(2));
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = thread_rng();
+        let mut list = ArenaLinkedList::new();
+        let mut expect: VecDeque<(Index, i32)> = VecDeque::new();
+        let mut removed = vec![];
+        for i in 0..3000 {
+            match rng.gen_range(0..5) {
+                0 => expect.push_front((list.push_front(i), i)),
+                1 => expect.push_back((list.push_back(i), i)),
+                2 if !expect.is_empty() => {
+                    let p = rng.gen_range(0..expect.len());
+                    let idx = list.insert_after(expect[p].0, i).unwrap();
+                    expect.insert(p + 1, (idx, i));
+                }
+                _ if !expect.is_empty() => {
+                    let p = rng.gen_range(0..expect.len());
+                    let (idx, val) = expect.remove(p).unwrap();
+                    assert_eq!(list.remove(idx), Some(val));
+                    removed.push(idx);
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(
+            list.iter().copied().collect::<Vec<i32>>(),
+            expect.iter().map(|x| x.1).collect::<Vec<i32>>()
+        );
+        assert_eq!(
+            list.indices().collect::<Vec<Index>>(),
+            expect.iter().map(|x| x.0).collect::<Vec<Index>>()
+        );
+        assert!(removed.iter().all(|&x| !list.contains(x)));
+    }
+}
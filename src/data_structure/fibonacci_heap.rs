@@ -0,0 +1,463 @@
+use std::cell::{Ref, RefCell};
+use std::rc::{Rc, Weak};
+
+use super::priority_queue::{BoxedCompare, Compare, HeapToken, MaxFirst, MinFirst};
+
+type Link<T> = Rc<RefCell<FibNode<T>>>;
+
+struct FibNode<T> {
+    val: T,
+    parent: Weak<RefCell<FibNode<T>>>,
+    children: Vec<Link<T>>,
+    // 在父节点的孩子列表（或根列表）中的下标
+    idx: usize,
+    // 成为孩子之后是否失去过孩子
+    marked: bool,
+}
+
+// 元素出堆后句柄失效，第二项是发出句柄的堆的标记
+pub struct Handle<T>(Weak<RefCell<FibNode<T>>>, Rc<HeapToken>);
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Handle(self.0.clone(), self.1.clone())
+    }
+}
+
+impl<T> Handle<T> {
+    pub fn is_valid(&self) -> bool {
+        self.0.strong_count() > 0
+    }
+}
+
+// 斐波那契堆：push / decrease_key 均摊 O(1)，pop / remove 均摊 O(log n)
+// 根保存在数组中而不是环形链表里，meld 需要 O(两个堆中较少的根数)
+pub struct FibonacciHeap<T, C = BoxedCompare<T>> {
+    roots: Vec<Link<T>>,
+    // 最小根在 roots 中的下标
+    min: Option<usize>,
+    len: usize,
+    cmp: C,
+    // 用来拒绝其他堆的句柄，始终是根标记
+    token: Rc<HeapToken>,
+}
+
+impl<T> FibonacciHeap<T> {
+    pub fn new<F>(nums: Option<Vec<T>>, cmp: F) -> Self
+    where
+        F: Fn(&T, &T) -> bool + 'static,
+    {
+        let cmp_func: BoxedCompare<T> = Box::new(cmp);
+        Self::with_compare(nums, cmp_func)
+    }
+}
+
+impl<T: PartialOrd> FibonacciHeap<T, MinFirst> {
+    pub fn new_min(nums: Option<Vec<T>>) -> Self {
+        Self::with_compare(nums, MinFirst)
+    }
+}
+
+impl<T: PartialOrd> FibonacciHeap<T, MaxFirst> {
+    pub fn new_max(nums: Option<Vec<T>>) -> Self {
+        Self::with_compare(nums, MaxFirst)
+    }
+}
+
+// 从列表中删除 idx 处的节点，并更新被换过来的节点的下标
+fn remove_at<T>(list: &mut Vec<Link<T>>, idx: usize) -> Link<T> {
+    let node = list.swap_remove(idx);
+    if idx < list.len() {
+        list[idx].borrow_mut().idx = idx;
+    }
+    node
+}
+
+impl<T, C> FibonacciHeap<T, C>
+where
+    C: Compare<T>,
+{
+    pub fn with_compare(nums: Option<Vec<T>>, cmp: C) -> Self {
+        let mut r = FibonacciHeap {
+            roots: vec![],
+            min: None,
+            len: 0,
+            cmp,
+            token: HeapToken::new(),
+        };
+        for x in nums.unwrap_or_default() {
+            r.push(x);
+        }
+        r
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn add_root(&mut self, node: Link<T>) {
+        {
+            let mut node_mut = node.borrow_mut();
+            node_mut.parent = Weak::new();
+            node_mut.marked = false;
+            node_mut.idx = self.roots.len();
+        }
+        let is_min = match self.min {
+            Some(m) => self
+                .cmp
+                .before(&node.borrow().val, &self.roots[m].borrow().val),
+            None => true,
+        };
+        if is_min {
+            self.min = Some(self.roots.len());
+        }
+        self.roots.push(node);
+    }
+
+    // 把 b 挂到 a 下面（或反过来），返回新的根
+    fn link(&self, a: Link<T>, b: Link<T>) -> Link<T> {
+        let (a, b) = if self.cmp.before(&b.borrow().val, &a.borrow().val) {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        {
+            let mut b_mut = b.borrow_mut();
+            b_mut.parent = Rc::downgrade(&a);
+            b_mut.idx = a.borrow().children.len();
+            b_mut.marked = false;
+        }
+        a.borrow_mut().children.push(b);
+        a
+    }
+
+    // 合并度数相同的根，直到所有根的度数互不相同
+    fn consolidate(&mut self) {
+        let mut table: Vec<Option<Link<T>>> = vec![];
+        for mut node in std::mem::take(&mut self.roots) {
+            loop {
+                let degree = node.borrow().children.len();
+                if degree >= table.len() {
+                    table.resize_with(degree + 1, || None);
+                }
+                match table[degree].take() {
+                    Some(other) => node = self.link(node, other),
+                    None => {
+                        table[degree] = Some(node);
+                        break;
+                    }
+                }
+            }
+        }
+        self.min = None;
+        for node in table.into_iter().flatten() {
+            self.add_root(node);
+        }
+    }
+
+    fn cut(&mut self, node: &Link<T>, parent: &Link<T>) {
+        let idx = node.borrow().idx;
+        let node = remove_at(&mut parent.borrow_mut().children, idx);
+        self.add_root(node);
+    }
+
+    // 已经失去过一个孩子的祖先也要切下来
+    fn cascading_cut(&mut self, mut node: Link<T>) {
+        loop {
+            let Some(parent) = node.borrow().parent.upgrade() else {
+                return;
+            };
+            if !node.borrow().marked {
+                node.borrow_mut().marked = true;
+                return;
+            }
+            self.cut(&node, &parent);
+            node = parent;
+        }
+    }
+
+    pub fn push(&mut self, item: T) -> Handle<T> {
+        let node = Rc::new(RefCell::new(FibNode {
+            val: item,
+            parent: Weak::new(),
+            children: vec![],
+            idx: 0,
+            marked: false,
+        }));
+        let handle = Handle(Rc::downgrade(&node), self.token.clone());
+        self.add_root(node);
+        self.len += 1;
+        handle
+    }
+
+    // 句柄属于这个堆且元素还在堆中时返回节点
+    fn node(&self, handle: &Handle<T>) -> Option<Link<T>> {
+        if !Rc::ptr_eq(&HeapToken::find(&handle.1), &self.token) {
+            return None;
+        }
+        handle.0.upgrade()
+    }
+
+    pub fn peek(&self) -> Option<Ref<'_, T>> {
+        self.min
+            .map(|m| Ref::map(self.roots[m].borrow(), |x| &x.val))
+    }
+
+    pub fn get(&self, handle: &Handle<T>) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.node(handle).map(|x| x.borrow().val.clone())
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let node = remove_at(&mut self.roots, self.min?);
+        let children = std::mem::take(&mut node.borrow_mut().children);
+        self.roots.extend(children);
+        self.consolidate();
+        self.len -= 1;
+        match Rc::try_unwrap(node) {
+            Ok(x) => Some(x.into_inner().val),
+            Err(_) => panic!("fibonacci heap node still shared"),
+        }
+    }
+
+    // 提高元素的优先级，新值不能排在原值之后，否则返回 false
+    pub fn decrease_key(&mut self, handle: &Handle<T>, item: T) -> bool {
+        let Some(node) = self.node(handle) else {
+            return false;
+        };
+        if self.cmp.before(&node.borrow().val, &item) {
+            return false;
+        }
+        node.borrow_mut().val = item;
+        let parent = node.borrow().parent.upgrade();
+        match parent {
+            Some(parent) => {
+                if self.cmp.before(&node.borrow().val, &parent.borrow().val) {
+                    self.cut(&node, &parent);
+                    self.cascading_cut(parent);
+                }
+            }
+            None => {
+                let m = self.min.unwrap();
+                if self
+                    .cmp
+                    .before(&node.borrow().val, &self.roots[m].borrow().val)
+                {
+                    self.min = Some(node.borrow().idx);
+                }
+            }
+        }
+        true
+    }
+
+    pub fn remove(&mut self, handle: &Handle<T>) -> Option<T> {
+        let node = self.node(handle)?;
+        let parent = node.borrow().parent.upgrade();
+        if let Some(parent) = parent {
+            self.cut(&node, &parent);
+            self.cascading_cut(parent);
+        }
+        // 相当于把键减到负无穷后出堆
+        self.min = Some(node.borrow().idx);
+        drop(node);
+        self.pop()
+    }
+
+    // 合并另一个堆，根较少的一方逐个移入另一方，other 中的句柄仍然有效
+    // 两个堆的比较规则必须一致，用闭包构造的堆无法检查这一点
+    pub fn meld(&mut self, mut other: Self) {
+        if self.roots.len() < other.roots.len() {
+            std::mem::swap(&mut self.roots, &mut other.roots);
+            std::mem::swap(&mut self.min, &mut other.min);
+        }
+        for node in std::mem::take(&mut other.roots) {
+            self.add_root(node);
+        }
+        HeapToken::union(&other.token, &self.token);
+        self.len += other.len;
+        other.len = 0;
+    }
+
+    pub fn clear(&mut self) {
+        Self::free(std::mem::take(&mut self.roots));
+        self.min = None;
+        self.len = 0;
+    }
+
+    // 按出堆顺序返回所有元素
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut res = Vec::with_capacity(self.len);
+        while let Some(x) = self.pop() {
+            res.push(x);
+        }
+        res
+    }
+}
+
+impl<T, C> FibonacciHeap<T, C> {
+    // 非递归释放，避免深层的树递归析构时栈溢出
+    fn free(mut stack: Vec<Link<T>>) {
+        while let Some(node) = stack.pop() {
+            stack.append(&mut node.borrow_mut().children);
+        }
+    }
+}
+
+impl<T, C> Drop for FibonacciHeap<T, C> {
+    fn drop(&mut self) {
+        Self::free(std::mem::take(&mut self.roots));
+    }
+}
+
+impl<T, C> FromIterator<T> for FibonacciHeap<T, C>
+where
+    C: Compare<T> + Default,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::with_compare(Some(iter.into_iter().collect()), C::default())
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for FibonacciHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn test1() {
+        let mut heap = FibonacciHeap::new(Some(vec![4, 3, 1, 2]), |x, y| x < y);
+        assert_eq!(heap.peek().map(|x| *x), Some(1));
+        assert_eq!(heap.pop(), Some(1));
+        heap.push(-2);
+        assert_eq!(heap.pop(), Some(-2));
+        assert_eq!(heap.len(), 3);
+        assert_eq!(heap.into_sorted_vec(), [2, 3, 4]);
+
+        let heap: FibonacciHeap<i32, MaxFirst> = (0..100).collect();
+        assert_eq!(heap.into_sorted_vec(), (0..100).rev().collect::<Vec<i32>>());
+    }
+
+    #[test]
+    fn test_handle() {
+        let mut heap = FibonacciHeap::new_min(None);
+        let handles = (0..10).map(|x| heap.push(x * 10)).collect::<Vec<_>>();
+        assert_eq!(heap.pop(), Some(0));
+        // pop 之后剩余元素已经合并成树，下面的操作会触发切断
+        assert!(heap.decrease_key(&handles[9], 5));
+        assert!(!heap.decrease_key(&handles[8], 100));
+        assert_eq!(heap.remove(&handles[4]), Some(40));
+        assert_eq!(heap.remove(&handles[4]), None);
+        assert!(!heap.decrease_key(&handles[0], -1));
+
+        let mut other = FibonacciHeap::new_min(Some(vec![15, 25]));
+        let h = other.push(35);
+        heap.meld(other);
+        assert!(heap.decrease_key(&h, 1));
+        assert_eq!(
+            heap.into_sorted_vec(),
+            [1, 5, 10, 15, 20, 25, 30, 50, 60, 70, 80]
+        );
+    }
+
+    #[test]
+    fn test_foreign_handle() {
+        let mut a = FibonacciHeap::new_min(Some(vec![3, 1, 2]));
+        let mut b = FibonacciHeap::new_min(Some(vec![10, 20]));
+        let h = b.push(5);
+        b.pop();
+        let h2 = b.push(30);
+        // 另一个堆的句柄一律拒绝，两个堆都不受影响
+        assert!(!a.decrease_key(&h, 0));
+        assert!(!a.decrease_key(&h2, 0));
+        assert_eq!(a.remove(&h), None);
+        assert_eq!(a.remove(&h2), None);
+        assert_eq!(a.get(&h2), None);
+        assert_eq!(a.len(), 3);
+        assert_eq!(b.len(), 3);
+
+        // 合并之后句柄归属于新的堆
+        a.meld(b);
+        assert_eq!(a.get(&h2), Some(30));
+        assert!(a.decrease_key(&h2, 0));
+        assert_eq!(a.into_sorted_vec(), [0, 1, 2, 3, 10, 20]);
+    }
+
+    #[test]
+    fn test_meld_chain() {
+        // 每次并入新堆，最早的句柄要沿很长的标记链找到根
+        let chain = |n: i32| {
+            let mut heap = FibonacciHeap::new_min(None);
+            let first = heap.push(0);
+            for i in 1..n {
+                let mut next = FibonacciHeap::new_min(Some(vec![i]));
+                next.meld(heap);
+                heap = next;
+            }
+            (heap, first)
+        };
+        let (mut heap, first) = chain(100000);
+        assert!(heap.decrease_key(&first, -1));
+        assert_eq!(heap.pop(), Some(-1));
+        assert_eq!(heap.len(), 99999);
+        // 标记链不能递归释放
+        drop(chain(100000));
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = thread_rng();
+        let mut heap = FibonacciHeap::new_min(None);
+        let mut handles = vec![];
+        let mut vals: Vec<Option<i32>> = vec![];
+        for _ in 0..5000 {
+            match rng.gen_range(0..5) {
+                0 | 1 => {
+                    let x = rng.gen_range(0..1000);
+                    handles.push(heap.push(x));
+                    vals.push(Some(x));
+                }
+                2 if !handles.is_empty() => {
+                    let i = rng.gen_range(0..handles.len());
+                    if let Some(v) = vals[i] {
+                        let x = v - rng.gen_range(0..100);
+                        assert!(heap.decrease_key(&handles[i], x));
+                        vals[i] = Some(x);
+                    }
+                }
+                3 if !handles.is_empty() => {
+                    let i = rng.gen_range(0..handles.len());
+                    assert_eq!(heap.remove(&handles[i]), vals[i].take());
+                }
+                _ => {
+                    let min = vals.iter().flatten().min().copied();
+                    assert_eq!(heap.pop(), min);
+                    if let Some(i) =
+                        (0..vals.len()).find(|&i| vals[i].is_some() && !handles[i].is_valid())
+                    {
+                        assert_eq!(vals[i], min);
+                        vals[i] = None;
+                    }
+                }
+            }
+            assert_eq!(heap.len(), vals.iter().flatten().count());
+        }
+    }
+}
@@ -0,0 +1,219 @@
+// 以 usize 句柄索引的小根堆，支持修改、删除任意句柄对应的键
+pub struct IndexedPriorityQueue<T> {
+    // 堆中存放句柄
+    heap: Vec<usize>,
+    // 句柄在 heap 中的位置
+    pos: Vec<Option<usize>>,
+    keys: Vec<Option<T>>,
+}
+
+impl<T> IndexedPriorityQueue<T>
+where
+    T: Ord,
+{
+    // capacity 为初始句柄范围，插入更大的句柄时自动扩容
+    pub fn new(capacity: usize) -> Self {
+        IndexedPriorityQueue {
+            heap: vec![],
+            pos: (0..capacity).map(|_| None).collect(),
+            keys: (0..capacity).map(|_| None).collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn contains(&self, handle: usize) -> bool {
+        self.pos.get(handle).is_some_and(|x| x.is_some())
+    }
+
+    pub fn get(&self, handle: usize) -> Option<&T> {
+        self.keys.get(handle).and_then(|x| x.as_ref())
+    }
+
+    fn less(&self, i: usize, j: usize) -> bool {
+        self.keys[self.heap[i]] < self.keys[self.heap[j]]
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.heap.swap(i, j);
+        self.pos[self.heap[i]] = Some(i);
+        self.pos[self.heap[j]] = Some(j);
+    }
+
+    fn sift_up(&mut self, mut child: usize) {
+        while child > 0 {
+            let far = (child - 1) / 2;
+            if !self.less(child, far) {
+                break;
+            }
+            self.swap(child, far);
+            child = far;
+        }
+    }
+
+    fn sift_down(&mut self, mut far: usize) {
+        let len = self.len();
+        loop {
+            let mut child = far * 2 + 1;
+            if child >= len {
+                break;
+            }
+            if child + 1 < len && self.less(child + 1, child) {
+                child += 1;
+            }
+            if !self.less(child, far) {
+                break;
+            }
+            self.swap(child, far);
+            far = child;
+        }
+    }
+
+    // 句柄已存在时返回 false，不做修改
+    pub fn push(&mut self, handle: usize, key: T) -> bool {
+        if self.contains(handle) {
+            return false;
+        }
+        if handle >= self.keys.len() {
+            self.pos.resize_with(handle + 1, || None);
+            self.keys.resize_with(handle + 1, || None);
+        }
+        self.heap.push(handle);
+        self.pos[handle] = Some(self.len() - 1);
+        self.keys[handle] = Some(key);
+        self.sift_up(self.len() - 1);
+        true
+    }
+
+    pub fn peek(&self) -> Option<(usize, &T)> {
+        self.heap
+            .first()
+            .map(|&h| (h, self.keys[h].as_ref().unwrap()))
+    }
+
+    pub fn pop(&mut self) -> Option<(usize, T)> {
+        let handle = *self.heap.first()?;
+        self.remove(handle).map(|key| (handle, key))
+    }
+
+    pub fn remove(&mut self, handle: usize) -> Option<T> {
+        let p = (*self.pos.get(handle)?)?;
+        let last = self.len() - 1;
+        self.swap(p, last);
+        self.heap.pop();
+        self.pos[handle] = None;
+        if p < self.len() {
+            self.sift_up(p);
+            self.sift_down(p);
+        }
+        self.keys[handle].take()
+    }
+
+    // 修改句柄对应的键，句柄不存在时返回 false
+    pub fn change_key(&mut self, handle: usize, key: T) -> bool {
+        let Some(p) = self.pos.get(handle).copied().flatten() else {
+            return false;
+        };
+        self.keys[handle] = Some(key);
+        self.sift_up(p);
+        self.sift_down(p);
+        true
+    }
+
+    // 新的键必须不大于原来的键，否则返回 false 且不做修改
+    pub fn decrease_key(&mut self, handle: usize, key: T) -> bool {
+        if self.get(handle).is_none_or(|x| key > *x) {
+            return false;
+        }
+        self.change_key(handle, key)
+    }
+
+    // 新的键必须不小于原来的键，否则返回 false 且不做修改
+    pub fn increase_key(&mut self, handle: usize, key: T) -> bool {
+        if self.get(handle).is_none_or(|x| key < *x) {
+            return false;
+        }
+        self.change_key(handle, key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::IndexedPriorityQueue;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn test1() {
+        let mut que = IndexedPriorityQueue::new(4);
+        assert!(que.push(0, 10));
+        assert!(que.push(1, 5));
+        assert!(que.push(2, 7));
+        assert!(!que.push(1, 1));
+        assert_eq!(que.peek(), Some((1, &5)));
+
+        assert!(que.decrease_key(0, 1));
+        assert!(!que.decrease_key(2, 100));
+        assert!(!que.decrease_key(3, 0));
+        assert_eq!(que.peek(), Some((0, &1)));
+
+        assert!(que.increase_key(0, 20));
+        assert!(!que.increase_key(1, 0));
+        assert_eq!(que.remove(2), Some(7));
+        assert!(!que.contains(2));
+        assert_eq!(que.remove(2), None);
+
+        assert_eq!(que.pop(), Some((1, 5)));
+        assert_eq!(que.pop(), Some((0, 20)));
+        assert_eq!(que.pop(), None);
+        assert!(que.is_empty());
+    }
+
+    #[test]
+    fn test_grow() {
+        let mut que = IndexedPriorityQueue::new(0);
+        que.push(100, 'a');
+        assert!(que.contains(100));
+        assert!(!que.contains(1000));
+        assert_eq!(que.get(100), Some(&'a'));
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = thread_rng();
+        let n = 500;
+        let mut que = IndexedPriorityQueue::new(n);
+        let mut keys = vec![None; n];
+        for _ in 0..5000 {
+            let h = rng.gen_range(0..n);
+            let k = rng.gen_range(0..1000);
+            match rng.gen_range(0..4) {
+                0 => {
+                    if que.push(h, k) {
+                        keys[h] = Some(k);
+                    }
+                }
+                1 => {
+                    if que.change_key(h, k) {
+                        keys[h] = Some(k);
+                    }
+                }
+                2 => assert_eq!(que.remove(h), keys[h].take()),
+                _ => {
+                    let min = keys.iter().flatten().min().copied();
+                    let top = que.pop();
+                    assert_eq!(top.map(|x| x.1), min);
+                    if let Some((h, _)) = top {
+                        keys[h] = None;
+                    }
+                }
+            }
+            assert_eq!(que.len(), keys.iter().flatten().count());
+        }
+    }
+}
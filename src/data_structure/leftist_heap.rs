@@ -0,0 +1,618 @@
+use std::cell::{Ref, RefCell, RefMut};
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::rc::{Rc, Weak};
+
+use super::priority_queue::{BoxedCompare, Compare, HeapToken, MaxFirst, MinFirst};
+
+type Link<T> = Rc<RefCell<LeftistNode<T>>>;
+
+struct LeftistNode<T> {
+    val: T,
+    // 到最近空节点的距离，左孩子不小于右孩子
+    rank: usize,
+    left: Option<Link<T>>,
+    right: Option<Link<T>>,
+    parent: Weak<RefCell<LeftistNode<T>>>,
+}
+
+fn rank<T>(node: &Option<Link<T>>) -> usize {
+    node.as_ref().map_or(0, |x| x.borrow().rank)
+}
+
+fn into_val<T>(node: Link<T>) -> T {
+    match Rc::try_unwrap(node) {
+        Ok(x) => x.into_inner().val,
+        Err(_) => panic!("leftist heap node still shared"),
+    }
+}
+
+// 元素出堆后句柄失效，第二项是发出句柄的堆的标记
+pub struct Handle<T>(Weak<RefCell<LeftistNode<T>>>, Rc<HeapToken>);
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Handle(self.0.clone(), self.1.clone())
+    }
+}
+
+impl<T> Handle<T> {
+    pub fn is_valid(&self) -> bool {
+        self.0.strong_count() > 0
+    }
+}
+
+// 左偏堆：push / pop / meld 均为 O(log n)
+pub struct LeftistHeap<T, C = BoxedCompare<T>> {
+    root: Option<Link<T>>,
+    len: usize,
+    cmp: C,
+    // 用来拒绝其他堆的句柄，始终是根标记
+    token: Rc<HeapToken>,
+}
+
+impl<T> LeftistHeap<T> {
+    pub fn new<F>(nums: Option<Vec<T>>, cmp: F) -> Self
+    where
+        F: Fn(&T, &T) -> bool + 'static,
+    {
+        let cmp_func: BoxedCompare<T> = Box::new(cmp);
+        Self::with_compare(nums, cmp_func)
+    }
+}
+
+impl<T: PartialOrd> LeftistHeap<T, MinFirst> {
+    pub fn new_min(nums: Option<Vec<T>>) -> Self {
+        Self::with_compare(nums, MinFirst)
+    }
+}
+
+impl<T: PartialOrd> LeftistHeap<T, MaxFirst> {
+    pub fn new_max(nums: Option<Vec<T>>) -> Self {
+        Self::with_compare(nums, MaxFirst)
+    }
+}
+
+impl<T, C> LeftistHeap<T, C>
+where
+    C: Compare<T>,
+{
+    pub fn with_compare(nums: Option<Vec<T>>, cmp: C) -> Self {
+        let mut r = LeftistHeap {
+            root: None,
+            len: 0,
+            cmp,
+            token: HeapToken::new(),
+        };
+        for x in nums.unwrap_or_default() {
+            r.push(x);
+        }
+        r
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // 沿右链合并，递归深度为 O(log n)
+    fn merge(&self, a: Option<Link<T>>, b: Option<Link<T>>) -> Option<Link<T>> {
+        let (a, b) = match (a, b) {
+            (None, x) | (x, None) => return x,
+            (Some(a), Some(b)) => {
+                if self.cmp.before(&b.borrow().val, &a.borrow().val) {
+                    (b, a)
+                } else {
+                    (a, b)
+                }
+            }
+        };
+        let right = a.borrow_mut().right.take();
+        let right = self.merge(right, Some(b));
+        if let Some(r) = &right {
+            r.borrow_mut().parent = Rc::downgrade(&a);
+        }
+        {
+            let mut a_mut = a.borrow_mut();
+            a_mut.right = right;
+            Self::fix_rank(&mut a_mut);
+        }
+        Some(a)
+    }
+
+    // 保持左偏性质，返回 rank 是否改变
+    fn fix_rank(node: &mut LeftistNode<T>) -> bool {
+        if rank(&node.left) < rank(&node.right) {
+            std::mem::swap(&mut node.left, &mut node.right);
+        }
+        let new_rank = rank(&node.right) + 1;
+        let changed = new_rank != node.rank;
+        node.rank = new_rank;
+        changed
+    }
+
+    // 把以 node 为根的子树从树中摘下，并向上修正 rank
+    fn cut(node: &Link<T>) {
+        let parent = std::mem::take(&mut node.borrow_mut().parent).upgrade();
+        let Some(parent) = parent else {
+            return;
+        };
+        {
+            let mut p = parent.borrow_mut();
+            if p.left.as_ref().is_some_and(|x| Rc::ptr_eq(x, node)) {
+                p.left = None;
+            } else {
+                p.right = None;
+            }
+        }
+        let mut cur = Some(parent);
+        while let Some(p) = cur {
+            if !Self::fix_rank(&mut p.borrow_mut()) {
+                break;
+            }
+            cur = p.borrow().parent.upgrade();
+        }
+    }
+
+    // 摘下 node 的两个孩子并合并
+    fn merge_children(&self, node: &Link<T>) -> Option<Link<T>> {
+        let (left, right) = {
+            let mut node_mut = node.borrow_mut();
+            (node_mut.left.take(), node_mut.right.take())
+        };
+        for child in left.iter().chain(right.iter()) {
+            child.borrow_mut().parent = Weak::new();
+        }
+        self.merge(left, right)
+    }
+
+    fn is_root(&self, node: &Link<T>) -> bool {
+        self.root.as_ref().is_some_and(|x| Rc::ptr_eq(x, node))
+    }
+
+    pub fn push(&mut self, item: T) -> Handle<T> {
+        let node = Rc::new(RefCell::new(LeftistNode {
+            val: item,
+            rank: 1,
+            left: None,
+            right: None,
+            parent: Weak::new(),
+        }));
+        let handle = Handle(Rc::downgrade(&node), self.token.clone());
+        let root = self.root.take();
+        self.root = self.merge(root, Some(node));
+        self.len += 1;
+        handle
+    }
+
+    // 句柄属于这个堆且元素还在堆中时返回节点
+    fn node(&self, handle: &Handle<T>) -> Option<Link<T>> {
+        if !Rc::ptr_eq(&HeapToken::find(&handle.1), &self.token) {
+            return None;
+        }
+        handle.0.upgrade()
+    }
+
+    pub fn peek(&self) -> Option<Ref<'_, T>> {
+        self.root.as_ref().map(|x| Ref::map(x.borrow(), |x| &x.val))
+    }
+
+    // 返回堆顶的可变访问，PeekMut 析构时重新调整堆
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, C>> {
+        if self.empty() {
+            return None;
+        }
+        Some(PeekMut {
+            heap: self,
+            changed: false,
+        })
+    }
+
+    pub fn get(&self, handle: &Handle<T>) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.node(handle).map(|x| x.borrow().val.clone())
+    }
+
+    // 堆顶的值被修改后重新放入堆中
+    fn fix_root(&mut self) {
+        if let Some(root) = self.root.take() {
+            let rest = self.merge_children(&root);
+            root.borrow_mut().rank = 1;
+            self.root = self.merge(rest, Some(root));
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let root = self.root.take()?;
+        self.root = self.merge_children(&root);
+        self.len -= 1;
+        Some(into_val(root))
+    }
+
+    // 提高元素的优先级，新值不能排在原值之后，否则返回 false
+    pub fn decrease_key(&mut self, handle: &Handle<T>, item: T) -> bool {
+        let Some(node) = self.node(handle) else {
+            return false;
+        };
+        if self.cmp.before(&node.borrow().val, &item) {
+            return false;
+        }
+        node.borrow_mut().val = item;
+        if !self.is_root(&node) {
+            Self::cut(&node);
+            let root = self.root.take();
+            self.root = self.merge(root, Some(node));
+        }
+        true
+    }
+
+    pub fn remove(&mut self, handle: &Handle<T>) -> Option<T> {
+        let node = self.node(handle)?;
+        if self.is_root(&node) {
+            drop(node);
+            return self.pop();
+        }
+        Self::cut(&node);
+        let sub = self.merge_children(&node);
+        let root = self.root.take();
+        self.root = self.merge(root, sub);
+        self.len -= 1;
+        Some(into_val(node))
+    }
+
+    // 合并另一个堆，other 中的句柄仍然有效
+    // 两个堆的比较规则必须一致，用闭包构造的堆无法检查这一点
+    pub fn meld(&mut self, mut other: Self) {
+        let root = self.root.take();
+        self.root = self.merge(root, other.root.take());
+        HeapToken::union(&other.token, &self.token);
+        self.len += other.len;
+        other.len = 0;
+    }
+
+    pub fn clear(&mut self) {
+        Self::free(self.root.take());
+        self.len = 0;
+    }
+
+    // 按出堆顺序返回所有元素
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut res = Vec::with_capacity(self.len);
+        while let Some(x) = self.pop() {
+            res.push(x);
+        }
+        res
+    }
+}
+
+impl<T, C> LeftistHeap<T, C> {
+    // 按节点的存储顺序遍历，不保证优先级顺序
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            stack: self.root.iter().cloned().collect(),
+            heap: PhantomData,
+        }
+    }
+
+    // 按节点的存储顺序取出所有元素，堆被清空，句柄全部失效
+    pub fn drain(&mut self) -> Drain<T> {
+        Drain {
+            stack: self.root.take().into_iter().collect(),
+            len: std::mem::take(&mut self.len),
+        }
+    }
+
+    pub fn into_vec(mut self) -> Vec<T> {
+        self.drain().collect()
+    }
+
+    // 左链可能很长，非递归释放
+    fn free(root: Option<Link<T>>) {
+        let mut stack: Vec<Link<T>> = root.into_iter().collect();
+        while let Some(node) = stack.pop() {
+            let mut node_mut = node.borrow_mut();
+            stack.extend(node_mut.left.take());
+            stack.extend(node_mut.right.take());
+        }
+    }
+}
+
+impl<T, C> Drop for LeftistHeap<T, C> {
+    fn drop(&mut self) {
+        Self::free(self.root.take());
+    }
+}
+
+impl<T, C> FromIterator<T> for LeftistHeap<T, C>
+where
+    C: Compare<T> + Default,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::with_compare(Some(iter.into_iter().collect()), C::default())
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for LeftistHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+// 堆顶的可变访问，修改过堆顶时析构会重新调整堆
+// 节点放在 RefCell 中，无法实现 Deref，通过 get / get_mut 访问堆顶
+pub struct PeekMut<'a, T, C: Compare<T>> {
+    heap: &'a mut LeftistHeap<T, C>,
+    changed: bool,
+}
+
+impl<T, C: Compare<T>> PeekMut<'_, T, C> {
+    pub fn get(&self) -> Ref<'_, T> {
+        self.heap.peek().unwrap()
+    }
+
+    pub fn get_mut(&mut self) -> RefMut<'_, T> {
+        self.changed = true;
+        let root = self.heap.root.as_ref().unwrap();
+        RefMut::map(root.borrow_mut(), |x| &mut x.val)
+    }
+
+    pub fn pop(this: Self) -> T {
+        let mut this = ManuallyDrop::new(this);
+        this.heap.pop().unwrap()
+    }
+}
+
+impl<T, C: Compare<T>> Drop for PeekMut<'_, T, C> {
+    fn drop(&mut self) {
+        if self.changed {
+            self.heap.fix_root();
+        }
+    }
+}
+
+// 元素放在 RefCell 中，无法返回引用，只能复制出来
+pub struct Iter<'a, T> {
+    stack: Vec<Link<T>>,
+    heap: PhantomData<&'a T>,
+}
+
+impl<T: Clone> Iterator for Iter<'_, T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        let node = self.stack.pop()?;
+        let node = node.borrow();
+        self.stack.extend(node.right.clone());
+        self.stack.extend(node.left.clone());
+        Some(node.val.clone())
+    }
+}
+
+pub struct Drain<T> {
+    stack: Vec<Link<T>>,
+    len: usize,
+}
+
+impl<T> Iterator for Drain<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        let node = self.stack.pop()?;
+        {
+            let mut node_mut = node.borrow_mut();
+            self.stack.extend(node_mut.right.take());
+            self.stack.extend(node_mut.left.take());
+        }
+        self.len -= 1;
+        Some(into_val(node))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+// 没取完的节点也要逐个释放，避免递归析构
+impl<T> Drop for Drain<T> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+// 按出堆顺序产生元素
+pub struct IntoIter<T, C: Compare<T>> {
+    heap: LeftistHeap<T, C>,
+}
+
+impl<T, C: Compare<T>> Iterator for IntoIter<T, C> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        self.heap.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.len(), Some(self.heap.len()))
+    }
+}
+
+impl<T, C: Compare<T>> IntoIterator for LeftistHeap<T, C> {
+    type Item = T;
+    type IntoIter = IntoIter<T, C>;
+    fn into_iter(self) -> IntoIter<T, C> {
+        IntoIter { heap: self }
+    }
+}
+
+impl<'a, T: Clone, C> IntoIterator for &'a LeftistHeap<T, C> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn test1() {
+        let mut heap = LeftistHeap::new(Some(vec![4, 3, 1, 2]), |x, y| x > y);
+        assert_eq!(heap.peek().map(|x| *x), Some(4));
+        assert_eq!(heap.pop(), Some(4));
+        heap.push(10);
+        assert_eq!(heap.pop(), Some(10));
+        assert_eq!(heap.len(), 3);
+        assert_eq!(heap.into_sorted_vec(), [3, 2, 1]);
+
+        let heap: LeftistHeap<i32, MinFirst> = (0..100).rev().collect();
+        assert_eq!(heap.into_sorted_vec(), (0..100).collect::<Vec<i32>>());
+    }
+
+    #[test]
+    fn test_meld() {
+        let mut a = LeftistHeap::new_max(Some(vec![5, 1, 9]));
+        let mut b = LeftistHeap::new_max(Some(vec![4, 8]));
+        let h = b.push(2);
+        a.meld(b);
+        assert!(a.decrease_key(&h, 20));
+        assert!(!a.decrease_key(&h, 3));
+        assert_eq!(a.into_sorted_vec(), [20, 9, 8, 5, 4, 1]);
+    }
+
+    #[test]
+    fn test_foreign_handle() {
+        let mut a = LeftistHeap::new_min(Some(vec![3, 1, 2]));
+        let mut b = LeftistHeap::new_min(Some(vec![10, 20]));
+        let h = b.push(5);
+        b.pop();
+        let h2 = b.push(30);
+        // 另一个堆的句柄一律拒绝，两个堆都不受影响
+        assert!(!a.decrease_key(&h, 0));
+        assert!(!a.decrease_key(&h2, 0));
+        assert_eq!(a.remove(&h), None);
+        assert_eq!(a.remove(&h2), None);
+        assert_eq!(a.get(&h2), None);
+        assert_eq!(a.len(), 3);
+        assert_eq!(b.len(), 3);
+
+        // 合并之后句柄归属于新的堆
+        a.meld(b);
+        assert_eq!(a.get(&h2), Some(30));
+        assert!(a.decrease_key(&h2, 0));
+        assert_eq!(a.into_sorted_vec(), [0, 1, 2, 3, 10, 20]);
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut heap = LeftistHeap::new_min(Some(vec![5, 1, 4, 2, 3]));
+        let mut all = heap.iter().collect::<Vec<i32>>();
+        all.sort();
+        assert_eq!(all, [1, 2, 3, 4, 5]);
+        assert_eq!((&heap).into_iter().count(), 5);
+
+        let h = heap.push(0);
+        let mut all = heap.drain().collect::<Vec<i32>>();
+        all.sort();
+        assert_eq!(all, [0, 1, 2, 3, 4, 5]);
+        assert!(heap.is_empty());
+        assert!(!h.is_valid());
+
+        // 只取一部分，剩下的节点随 Drain 释放
+        heap.extend(0..100000);
+        assert_eq!(heap.drain().take(3).count(), 3);
+        assert!(heap.is_empty());
+        heap.extend([7, 8]);
+        let mut all = heap.into_vec();
+        all.sort();
+        assert_eq!(all, [7, 8]);
+    }
+
+    #[test]
+    fn test_peek_mut() {
+        let mut heap = LeftistHeap::new_max(Some(vec![3, 8, 5]));
+        let h = heap.push(6);
+        if let Some(mut top) = heap.peek_mut() {
+            assert_eq!(*top.get(), 8);
+            *top.get_mut() = 1;
+        }
+        assert_eq!(heap.peek().map(|x| *x), Some(6));
+        assert!(heap.decrease_key(&h, 9));
+
+        let top = heap.peek_mut().map(PeekMut::pop);
+        assert_eq!(top, Some(9));
+        assert!(!h.is_valid());
+        assert_eq!(heap.into_iter().collect::<Vec<i32>>(), [5, 3, 1]);
+    }
+
+    #[test]
+    fn test_meld_chain() {
+        // 每次并入新堆，最早的句柄要沿很长的标记链找到根
+        let chain = |n: i32| {
+            let mut heap = LeftistHeap::new_min(None);
+            let first = heap.push(0);
+            for i in 1..n {
+                let mut next = LeftistHeap::new_min(Some(vec![i]));
+                next.meld(heap);
+                heap = next;
+            }
+            (heap, first)
+        };
+        let (mut heap, first) = chain(100000);
+        assert!(heap.decrease_key(&first, -1));
+        assert_eq!(heap.pop(), Some(-1));
+        assert_eq!(heap.len(), 99999);
+        // 标记链不能递归释放
+        drop(chain(100000));
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = thread_rng();
+        let mut heap = LeftistHeap::new_min(None);
+        let mut handles = vec![];
+        let mut vals: Vec<Option<i32>> = vec![];
+        for _ in 0..3000 {
+            match rng.gen_range(0..5) {
+                0 | 1 => {
+                    let x = rng.gen_range(0..1000);
+                    handles.push(heap.push(x));
+                    vals.push(Some(x));
+                }
+                2 if !handles.is_empty() => {
+                    let i = rng.gen_range(0..handles.len());
+                    if let Some(v) = vals[i] {
+                        let x = v - rng.gen_range(0..100);
+                        assert!(heap.decrease_key(&handles[i], x));
+                        vals[i] = Some(x);
+                    }
+                }
+                3 if !handles.is_empty() => {
+                    let i = rng.gen_range(0..handles.len());
+                    assert_eq!(heap.remove(&handles[i]), vals[i].take());
+                }
+                _ => {
+                    let min = vals.iter().flatten().min().copied();
+                    assert_eq!(heap.pop(), min);
+                    if let Some(i) =
+                        (0..vals.len()).find(|&i| vals[i].is_some() && !handles[i].is_valid())
+                    {
+                        assert_eq!(vals[i], min);
+                        vals[i] = None;
+                    }
+                }
+            }
+            assert_eq!(heap.len(), vals.iter().flatten().count());
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use std::borrow::Borrow;
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use super::linked_list::{LinkedList, Node};
+use super::lru_cache::{node_val, node_val_mut, EvictCallback};
+
+// 访问次数相同的元素，链表头部为最近使用的元素
+struct Bucket<K, V> {
+    freq: usize,
+    items: LinkedList<(K, V)>,
+}
+
+impl<K, V> Bucket<K, V> {
+    fn new(freq: usize) -> Self {
+        Bucket {
+            freq,
+            items: LinkedList::new(),
+        }
+    }
+}
+
+// (所在的桶, 桶中的节点)
+type Entry<K, V> = (Node<Bucket<K, V>>, Node<(K, V)>);
+
+fn bucket<K, V>(node: &Node<Bucket<K, V>>) -> RefMut<'_, Bucket<K, V>> {
+    RefMut::map(node.borrow_mut(), |x| x.val_mut().unwrap())
+}
+
+// 最不经常使用缓存，次数相同时淘汰最久未使用的元素
+// 非空的桶按访问次数升序串成链表，访问时元素只会移到相邻的桶，所有操作均为 O(1)
+pub struct LfuCache<K, V> {
+    map: HashMap<K, Entry<K, V>>,
+    buckets: LinkedList<Bucket<K, V>>,
+    capacity: usize,
+    on_evict: Option<EvictCallback<K, V>>,
+}
+
+impl<K, V> LfuCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    pub fn new(capacity: usize) -> Self {
+        LfuCache {
+            map: HashMap::with_capacity(capacity),
+            buckets: LinkedList::new(),
+            capacity,
+            on_evict: None,
+        }
+    }
+
+    pub fn with_evict_callback<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(K, V) + 'static,
+    {
+        self.on_evict = Some(Box::new(f));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    pub fn frequency<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map
+            .get(key)
+            .map(|x| RefCell::borrow(&x.0).val().unwrap().freq)
+    }
+
+    // 从桶中摘下元素，桶为空时一并删除
+    fn take_from_bucket(&mut self, b: &Node<Bucket<K, V>>, node: &Node<(K, V)>) -> (K, V) {
+        let mut bk = bucket(b);
+        let item = bk.items.erase(node).unwrap();
+        let empty = bk.items.is_empty();
+        drop(bk);
+        if empty {
+            self.buckets.erase(b);
+        }
+        item
+    }
+
+    // 访问次数加一，元素移到紧跟在后面的桶中，返回新的节点
+    fn touch<Q>(&mut self, key: &Q) -> Option<&Node<(K, V)>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (b, node) = self.map.get(key)?.clone();
+        let freq = bucket(&b).freq;
+        let next = self
+            .buckets
+            .next_node(&b)
+            .filter(|x| bucket(x).freq == freq + 1);
+        // 先建好下一个桶再摘下元素，否则当前桶被删除后就找不到插入位置
+        let target = match next {
+            Some(x) => x,
+            None => self.buckets.insert_back(&b, Bucket::new(freq + 1)).unwrap(),
+        };
+        let item = self.take_from_bucket(&b, &node);
+        let node = bucket(&target).items.push_front_node(item);
+        let entry = self.map.get_mut(key).unwrap();
+        *entry = (target, node);
+        Some(&entry.1)
+    }
+
+    pub fn get<Q>(&mut self, key: &Q) -> Option<Ref<'_, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.touch(key).map(node_val)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<RefMut<'_, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.touch(key).map(node_val_mut)
+    }
+
+    // 访问元素但不增加访问次数
+    pub fn peek<Q>(&self, key: &Q) -> Option<Ref<'_, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get(key).map(|x| node_val(&x.1))
+    }
+
+    // 键已存在时更新值、增加访问次数并返回旧值
+    pub fn put(&mut self, key: K, val: V) -> Option<V> {
+        if let Some(node) = self.touch(&key) {
+            return Some(std::mem::replace(&mut *node_val_mut(node), val));
+        }
+        if self.capacity == 0 {
+            self.evict(key, val);
+            return None;
+        }
+        if self.map.len() >= self.capacity {
+            self.pop_evict();
+        }
+        let first = self.buckets.front_node().filter(|x| bucket(x).freq == 1);
+        let b = match first {
+            Some(x) => x,
+            None => self.buckets.push_front_node(Bucket::new(1)),
+        };
+        let node = bucket(&b).items.push_front_node((key.clone(), val));
+        self.map.insert(key, (b, node));
+        None
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (b, node) = self.map.remove(key)?;
+        Some(self.take_from_bucket(&b, &node).1)
+    }
+
+    // 弹出访问次数最少的元素中最久未使用的一个
+    pub fn pop_lfu(&mut self) -> Option<(K, V)> {
+        let first = self.buckets.front_node()?;
+        let item = bucket(&first).items.pop_back().unwrap();
+        if bucket(&first).items.is_empty() {
+            self.buckets.erase(&first);
+        }
+        self.map.remove(&item.0);
+        Some(item)
+    }
+
+    fn evict(&mut self, key: K, val: V) {
+        if let Some(f) = self.on_evict.as_mut() {
+            f(key, val);
+        }
+    }
+
+    fn pop_evict(&mut self) {
+        if let Some((key, val)) = self.pop_lfu() {
+            self.evict(key, val);
+        }
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.map.len() > capacity {
+            self.pop_evict();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.buckets.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn test1() {
+        let mut cache = LfuCache::new(2);
+        cache.put(1, 1);
+        cache.put(2, 2);
+        assert_eq!(cache.get(&1).map(|x| *x), Some(1));
+        cache.put(3, 3);
+        // 2 访问次数最少，被淘汰
+        assert!(!cache.contains(&2));
+        assert_eq!(cache.get(&3).map(|x| *x), Some(3));
+        // 1 和 3 次数相同，1 最久未使用
+        cache.put(4, 4);
+        assert!(!cache.contains(&1));
+        assert_eq!(cache.frequency(&3), Some(2));
+        assert_eq!(cache.frequency(&4), Some(1));
+        assert_eq!(cache.put(4, 40), Some(4));
+        assert_eq!(cache.peek(&4).map(|x| *x), Some(40));
+        assert_eq!(cache.frequency(&4), Some(2));
+        *cache.get_mut(&4).unwrap() += 1;
+        assert_eq!(cache.pop_lfu(), Some((3, 3)));
+        assert_eq!(cache.remove(&4), Some(41));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_capacity() {
+        let mut cache = LfuCache::new(4);
+        for i in 0..4 {
+            cache.put(i, i);
+            for _ in 0..i {
+                cache.get(&i);
+            }
+        }
+        cache.remove(&1);
+        cache.set_capacity(2);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.contains(&2) && cache.contains(&3));
+        cache.put(5, 5);
+        assert!(!cache.contains(&2));
+    }
+
+    // 与按 (次数, 最近访问时间) 线性查找的实现对比，remove 之后最小次数也要正确
+    #[test]
+    fn test_random() {
+        let mut rng = thread_rng();
+        let capacity = 8;
+        let mut cache = LfuCache::new(capacity);
+        let mut expect: HashMap<i32, (i32, usize, usize)> = HashMap::new();
+        for time in 0..5000 {
+            let key = rng.gen_range(0..20);
+            let op = rng.gen_range(0..10);
+            let victim = expect.iter().min_by_key(|(_, x)| (x.1, x.2)).map(|x| *x.0);
+            if op == 0 {
+                let e = expect.remove(&key).map(|x| x.0);
+                assert_eq!(cache.remove(&key), e);
+            } else if op == 1 {
+                let e = victim.map(|k| (k, expect.remove(&k).unwrap().0));
+                assert_eq!(cache.pop_lfu(), e);
+            } else if op < 6 {
+                let val = cache.get(&key).map(|x| *x);
+                let e = expect.get_mut(&key).map(|x| {
+                    x.1 += 1;
+                    x.2 = time;
+                    x.0
+                });
+                assert_eq!(val, e);
+                assert_eq!(cache.frequency(&key), expect.get(&key).map(|x| x.1));
+            } else {
+                let val = rng.gen();
+                if let Some(x) = expect.get_mut(&key) {
+                    *x = (val, x.1 + 1, time);
+                } else {
+                    if expect.len() == capacity {
+                        expect.remove(&victim.unwrap());
+                    }
+                    expect.insert(key, (val, 1, time));
+                }
+                cache.put(key, val);
+            }
+            assert_eq!(cache.len(), expect.len());
+        }
+    }
+}
@@ -1,103 +1,287 @@
-use std::cell::RefCell;
-use std::marker::PhantomData;
-use std::ops::Deref;
+use std::cell::{Ref, RefCell, RefMut};
 use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 //use std::collections::linked_list
 
-type Node<T> = Rc<RefCell<LinkedListNode<T>>>;
+static LIST_ID: AtomicUsize = AtomicUsize::new(0);
+
+pub(super) type Node<T> = Rc<RefCell<LinkedListNode<T>>>;
 
 #[derive(Debug, PartialEq, Eq)]
-struct LinkedListNode<T> {
+pub(super) struct LinkedListNode<T> {
     val: Option<T>,
     next: Option<Node<T>>,
     pre: Option<Node<T>>,
+    // 创建节点的链表编号，split / splice 转移节点时不更新
+    list: usize,
 }
 
 impl<T> LinkedListNode<T> {
-    pub fn new(val: Option<T>) -> Node<T> {
+    fn new(val: Option<T>) -> Node<T> {
         Rc::new(RefCell::new(LinkedListNode {
-            val: val,
+            val,
             next: None,
             pre: None,
+            list: usize::MAX,
         }))
     }
+
+    // 哨兵节点和已删除节点的值为 None
+    pub(super) fn val(&self) -> Option<&T> {
+        self.val.as_ref()
+    }
+
+    pub(super) fn val_mut(&mut self) -> Option<&mut T> {
+        self.val.as_mut()
+    }
 }
 
-struct LinkedList<T> {
+pub struct LinkedList<T> {
     head: Node<T>,
     tail: Node<T>,
     size: usize,
+    id: usize,
 }
 
-impl<T> LinkedList<T>
-where
-    T: PartialEq,
-{
+impl<T> LinkedList<T> {
     pub fn new() -> Self {
         let head = LinkedListNode::new(None);
         let tail = LinkedListNode::new(None);
         head.borrow_mut().next = Some(tail.clone());
         tail.borrow_mut().pre = Some(head.clone());
         LinkedList {
-            head: head,
-            tail: tail,
+            head,
+            tail,
             size: 0,
+            id: LIST_ID.fetch_add(1, Ordering::Relaxed),
         }
     }
 
-    pub fn insert_back(&mut self, pos: &Node<T>, val: T) -> Node<T> {
-        let new_node = LinkedListNode::new(Some(val));
-        let mut new_mut = new_node.borrow_mut();
-        new_mut.next = pos.borrow().next.clone();
-        new_mut.pre = Some(pos.clone());
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    // pos 是否为本链表创建、尚未删除的元素节点，用于检查缓存传入的节点
+    // 节点经 split / splice 转移后编号不再更新，这类节点不能再交给下面的节点接口
+    fn owns(&self, pos: &Node<T>) -> bool {
+        let pos = pos.borrow();
+        pos.list == self.id && pos.val.is_some() && pos.pre.is_some()
+    }
 
-        pos.borrow().next.as_ref().and_then(|p| {
-            p.borrow_mut().pre = Some(new_node.clone());
-            None::<T>
-        });
-        pos.borrow_mut().next = Some(new_node.clone());
+    fn new_node(&self, val: T) -> Node<T> {
+        let node = LinkedListNode::new(Some(val));
+        node.borrow_mut().list = self.id;
+        node
+    }
 
-        new_node.clone()
+    // 在 pos 之后插入，pos 不是本链表的元素时返回 None
+    pub(super) fn insert_back(&mut self, pos: &Node<T>, val: T) -> Option<Node<T>> {
+        if !self.owns(pos) {
+            return None;
+        }
+        Some(self.link_new(pos, val))
     }
 
-    pub fn insert_front(&mut self, pos: &Node<T>, val: T) -> Node<T> {
-        let new_node = LinkedListNode::new(Some(val));
-        let mut new_mut = new_node.borrow_mut();
-        new_mut.next = Some(pos.clone());
-        new_mut.pre = pos.borrow().pre.clone();
+    // 在 pos 之后插入，调用方保证 pos 是本链表的头哨兵或元素
+    fn link_new(&mut self, pos: &Node<T>, val: T) -> Node<T> {
+        let new_node = self.new_node(val);
+        self.link_after(pos, new_node.clone(), new_node.clone(), 1);
+        new_node
+    }
 
-        pos.borrow().pre.as_ref().and_then(|p| {
-            p.borrow_mut().next = Some(new_node.clone());
-            None::<T>
-        });
-        pos.borrow_mut().pre = Some(new_node.clone());
+    // 删除 pos 并返回它的值，pos 为哨兵、已被删除或属于其他链表时返回 None
+    pub(super) fn erase(&mut self, pos: &Node<T>) -> Option<T> {
+        if !self.owns(pos) {
+            return None;
+        }
+        self.remove(pos)
+    }
 
-        new_node.clone()
+    // 删除 pos 并返回它的值，调用方保证 pos 是本链表的元素
+    fn remove(&mut self, pos: &Node<T>) -> Option<T> {
+        self.detach(pos);
+        pos.borrow_mut().val.take()
     }
 
-    pub fn earse(&mut self, pos: &Node<T>) {
-        let pnext = pos.borrow().next.clone();
-        pos.borrow_mut().pre.as_ref().and_then(|p| {
-            p.borrow_mut().next = pnext;
-            None::<T>
-        });
+    pub fn push_back(&mut self, val: T) {
+        self.push_back_node(val);
     }
 
-    pub fn push_back(&mut self, val: T) -> Node<T> {
+    pub fn push_front(&mut self, val: T) {
+        self.push_front_node(val);
+    }
+
+    // 以下返回或接收节点的方法只给缓存使用，节点不对外暴露
+    pub(super) fn push_back_node(&mut self, val: T) -> Node<T> {
         let pos = self.tail.borrow().pre.clone().unwrap();
-        self.size += 1;
-        self.insert_back(&pos, val)
+        self.link_new(&pos, val)
     }
 
-    pub fn push_front(&mut self, val: T) -> Node<T> {
+    pub(super) fn push_front_node(&mut self, val: T) -> Node<T> {
         let pos = self.head.clone();
-        self.size += 1;
-        self.insert_back(&pos, val)
+        self.link_new(&pos, val)
+    }
+
+    pub(super) fn front_node(&self) -> Option<Node<T>> {
+        if self.is_empty() {
+            return None;
+        }
+        self.head.borrow().next.clone()
+    }
+
+    pub(super) fn back_node(&self) -> Option<Node<T>> {
+        if self.is_empty() {
+            return None;
+        }
+        self.tail.borrow().pre.clone()
+    }
+
+    // pos 之后的元素节点，pos 是最后一个元素或不属于本链表时返回 None
+    pub(super) fn next_node(&self, pos: &Node<T>) -> Option<Node<T>> {
+        if !self.owns(pos) {
+            return None;
+        }
+        let next = pos.borrow().next.clone()?;
+        next.borrow().val.as_ref()?;
+        Some(next)
+    }
+
+    pub(super) fn nodes(&self) -> Nodes<T> {
+        Nodes {
+            cur: self.head.clone(),
+        }
+    }
+
+    // 把 pos 移到链表头部，不重新分配节点
+    pub(super) fn move_to_front(&mut self, pos: &Node<T>) {
+        if self.owns(pos) {
+            self.detach(pos);
+            let head = self.head.clone();
+            self.link_after(&head, pos.clone(), pos.clone(), 1);
+        }
+    }
+
+    pub(super) fn move_to_back(&mut self, pos: &Node<T>) {
+        if self.owns(pos) {
+            self.detach(pos);
+            let last = self.tail.borrow().pre.clone().unwrap();
+            self.link_after(&last, pos.clone(), pos.clone(), 1);
+        }
+    }
+
+    // 把 pos 从链表中摘下但保留它的值
+    fn detach(&mut self, pos: &Node<T>) {
+        let mut pos_mut = pos.borrow_mut();
+        let pre = pos_mut.pre.take().unwrap();
+        let next = pos_mut.next.take().unwrap();
+        next.borrow_mut().pre = Some(pre.clone());
+        pre.borrow_mut().next = Some(next);
+        self.size -= 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        let node = self.front_node()?;
+        self.remove(&node)
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let node = self.back_node()?;
+        self.remove(&node)
+    }
+
+    // 断开所有节点之间的引用，外部持有的节点仍然保留各自的值
+    pub fn clear(&mut self) {
+        let mut cur = self.head.borrow_mut().next.take();
+        while let Some(node) = cur {
+            if Rc::ptr_eq(&node, &self.tail) {
+                break;
+            }
+            let mut node_mut = node.borrow_mut();
+            node_mut.pre = None;
+            cur = node_mut.next.take();
+        }
+        self.head.borrow_mut().next = Some(self.tail.clone());
+        self.tail.borrow_mut().pre = Some(self.head.clone());
+        self.size = 0;
+    }
+
+    pub fn cursor_front(&self) -> Cursor<'_, T> {
+        let pos = Position::new(self, 0);
+        Cursor { list: self, pos }
+    }
+
+    pub fn cursor_back(&self) -> Cursor<'_, T> {
+        let pos = Position::new(self, self.size.saturating_sub(1));
+        Cursor { list: self, pos }
+    }
+
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        let pos = Position::new(self, 0);
+        CursorMut { list: self, pos }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        let pos = Position::new(self, self.size.saturating_sub(1));
+        CursorMut { list: self, pos }
+    }
+
+    // 把 first..=last 这一段（共 n 个节点）接到 pos 之后，耗时 O(1)
+    fn link_after(&mut self, pos: &Node<T>, first: Node<T>, last: Node<T>, n: usize) {
+        let next = pos.borrow_mut().next.replace(first.clone());
+        if let Some(p) = &next {
+            p.borrow_mut().pre = Some(last.clone());
+        }
+        first.borrow_mut().pre = Some(pos.clone());
+        last.borrow_mut().next = next;
+        self.size += n;
+    }
+
+    // 取出全部节点，返回 (第一个, 最后一个, 个数)
+    fn take_all(&mut self) -> Option<(Node<T>, Node<T>, usize)> {
+        if self.is_empty() {
+            return None;
+        }
+        let first = self.head.borrow_mut().next.replace(self.tail.clone())?;
+        let last = self.tail.borrow_mut().pre.replace(self.head.clone())?;
+        first.borrow_mut().pre = None;
+        last.borrow_mut().next = None;
+        Some((first, last, std::mem::take(&mut self.size)))
+    }
+
+    // 把 first..=last 这一段摘下，组成新的链表
+    fn split_range(&mut self, first: Node<T>, last: Node<T>, n: usize) -> LinkedList<T> {
+        let mut res = LinkedList::new();
+        if n == 0 {
+            return res;
+        }
+        let pre = first.borrow_mut().pre.take().unwrap();
+        let next = last.borrow_mut().next.take().unwrap();
+        next.borrow_mut().pre = Some(pre.clone());
+        pre.borrow_mut().next = Some(next);
+        self.size -= n;
+        let head = res.head.clone();
+        res.link_after(&head, first, last, n);
+        res
+    }
+}
+
+// 元素放在 RefCell 中，无法返回跨越节点的引用，只能复制出来
+impl<T: Clone> LinkedList<T> {
+    pub fn front(&self) -> Option<T> {
+        self.front_node()?.borrow().val.clone()
+    }
+
+    pub fn back(&self) -> Option<T> {
+        self.back_node()?.borrow().val.clone()
     }
 
     pub fn iter(&self) -> Iter<T> {
         Iter {
-            cur: self.head.clone(),
+            nodes: self.nodes(),
         }
     }
 }
@@ -106,39 +290,324 @@ impl<T> LinkedList<T>
 where
     T: PartialEq,
 {
-    pub fn find(&self, val: &T, start_pos: Option<Node<T>>) -> Option<Node<T>> {
-        let mut pos = start_pos.unwrap_or(self.head.clone());
-        let t_val = Some(val);
-        loop {
-            if Rc::ptr_eq(&pos, &self.tail) {
-                break;
+    // 返回指向第一个等于 val 的元素的游标
+    pub fn find(&self, val: &T) -> Option<Cursor<'_, T>> {
+        let mut cursor = self.cursor_front();
+        while cursor.index().is_some() {
+            if cursor.current().is_some_and(|x| *x == *val) {
+                return Some(cursor);
             }
-            if pos.borrow().val.as_ref() == t_val {
-                return Some(pos);
-            }
-            let next = pos.borrow().next.clone().unwrap();
-            pos = next;
+            cursor.move_next();
         }
         None
     }
 }
 
-struct Iter<T> {
+impl<T> Default for LinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Extend<T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push_back(item);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = LinkedList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+// next 和 pre 互相引用构成环，必须手动断开才能释放
+impl<T> Drop for LinkedList<T> {
+    fn drop(&mut self) {
+        self.clear();
+        self.head.borrow_mut().next = None;
+        self.tail.borrow_mut().pre = None;
+    }
+}
+
+// 游标当前的位置，指向尾哨兵时表示位于链表首尾之间的空位置
+// 同时持有前后节点，便于返回它们的引用
+struct Position<T> {
     cur: Node<T>,
+    pre: Node<T>,
+    next: Node<T>,
+    // 空位置的下标为链表长度
+    index: usize,
 }
 
-impl<T> Iterator for Iter<T> {
-    type Item = Node<T>;
-    fn next(&mut self) -> Option<Self::Item> {
-        let next = self.cur.borrow().next.clone();
-        if let Some(p) = next {
-            self.cur = p.clone();
-            if self.cur.borrow().val.is_none() {
-                return None;
+impl<T> Position<T> {
+    fn new(list: &LinkedList<T>, index: usize) -> Self {
+        let cur = if list.is_empty() {
+            list.tail.clone()
+        } else if index == 0 {
+            list.head.borrow().next.clone().unwrap()
+        } else {
+            list.tail.borrow().pre.clone().unwrap()
+        };
+        let mut pos = Position {
+            pre: cur.clone(),
+            next: cur.clone(),
+            cur,
+            index,
+        };
+        pos.refresh(list);
+        pos
+    }
+
+    fn is_ghost(&self, list: &LinkedList<T>) -> bool {
+        Rc::ptr_eq(&self.cur, &list.tail)
+    }
+
+    // 当前节点变化或链表被修改后重新计算前后节点
+    fn refresh(&mut self, list: &LinkedList<T>) {
+        if self.is_ghost(list) {
+            self.pre = list.tail.borrow().pre.clone().unwrap();
+            self.next = list.head.borrow().next.clone().unwrap();
+            self.index = list.len();
+        } else {
+            let cur = self.cur.borrow();
+            self.pre = cur.pre.clone().unwrap();
+            self.next = cur.next.clone().unwrap();
+        }
+    }
+
+    fn set(&mut self, list: &LinkedList<T>, node: Node<T>, index: usize) {
+        self.cur = if Rc::ptr_eq(&node, &list.head) {
+            list.tail.clone()
+        } else {
+            node
+        };
+        self.index = index;
+        self.refresh(list);
+    }
+
+    fn move_next(&mut self, list: &LinkedList<T>) {
+        let index = if self.is_ghost(list) {
+            0
+        } else {
+            self.index + 1
+        };
+        self.set(list, self.next.clone(), index);
+    }
+
+    fn move_prev(&mut self, list: &LinkedList<T>) {
+        let index = if self.is_ghost(list) {
+            list.len().saturating_sub(1)
+        } else {
+            self.index.saturating_sub(1)
+        };
+        self.set(list, self.pre.clone(), index);
+    }
+}
+
+fn node_ref<T>(node: &Node<T>) -> Option<Ref<'_, T>> {
+    Ref::filter_map(node.borrow(), |x| x.val.as_ref()).ok()
+}
+
+fn node_mut<T>(node: &Node<T>) -> Option<RefMut<'_, T>> {
+    RefMut::filter_map(node.borrow_mut(), |x| x.val.as_mut()).ok()
+}
+
+// 只读游标，可以在链表上双向移动，越过首尾后停在空位置上
+pub struct Cursor<'a, T> {
+    list: &'a LinkedList<T>,
+    pos: Position<T>,
+}
+
+impl<T> Cursor<'_, T> {
+    // 位于空位置时返回 None
+    pub fn index(&self) -> Option<usize> {
+        (!self.pos.is_ghost(self.list)).then_some(self.pos.index)
+    }
+
+    pub fn move_next(&mut self) {
+        self.pos.move_next(self.list);
+    }
+
+    pub fn move_prev(&mut self) {
+        self.pos.move_prev(self.list);
+    }
+
+    pub fn current(&self) -> Option<Ref<'_, T>> {
+        node_ref(&self.pos.cur)
+    }
+
+    pub fn peek_next(&self) -> Option<Ref<'_, T>> {
+        node_ref(&self.pos.next)
+    }
+
+    pub fn peek_prev(&self) -> Option<Ref<'_, T>> {
+        node_ref(&self.pos.pre)
+    }
+}
+
+// 可修改链表的游标
+pub struct CursorMut<'a, T> {
+    list: &'a mut LinkedList<T>,
+    pos: Position<T>,
+}
+
+impl<T> CursorMut<'_, T> {
+    pub fn index(&self) -> Option<usize> {
+        (!self.pos.is_ghost(self.list)).then_some(self.pos.index)
+    }
+
+    pub fn move_next(&mut self) {
+        self.pos.move_next(self.list);
+    }
+
+    pub fn move_prev(&mut self) {
+        self.pos.move_prev(self.list);
+    }
+
+    pub fn current(&mut self) -> Option<RefMut<'_, T>> {
+        node_mut(&self.pos.cur)
+    }
+
+    pub fn peek_next(&mut self) -> Option<RefMut<'_, T>> {
+        node_mut(&self.pos.next)
+    }
+
+    pub fn peek_prev(&mut self) -> Option<RefMut<'_, T>> {
+        node_mut(&self.pos.pre)
+    }
+
+    pub fn as_cursor(&self) -> Cursor<'_, T> {
+        Cursor {
+            list: self.list,
+            pos: Position {
+                cur: self.pos.cur.clone(),
+                pre: self.pos.pre.clone(),
+                next: self.pos.next.clone(),
+                index: self.pos.index,
+            },
+        }
+    }
+
+    // 位于空位置时插入到链表头部
+    pub fn insert_after(&mut self, item: T) {
+        if self.pos.is_ghost(self.list) {
+            self.list.push_front(item);
+        } else {
+            let cur = self.pos.cur.clone();
+            self.list.link_new(&cur, item);
+        }
+        self.pos.refresh(self.list);
+    }
+
+    // 位于空位置时插入到链表尾部
+    pub fn insert_before(&mut self, item: T) {
+        if self.pos.is_ghost(self.list) {
+            self.list.push_back(item);
+        } else {
+            let pre = self.pos.pre.clone();
+            self.list.link_new(&pre, item);
+            self.pos.index += 1;
+        }
+        self.pos.refresh(self.list);
+    }
+
+    // 删除当前元素，游标移到下一个元素
+    pub fn remove_current(&mut self) -> Option<T> {
+        if self.pos.is_ghost(self.list) {
+            return None;
+        }
+        let cur = self.pos.cur.clone();
+        let next = self.pos.next.clone();
+        let res = self.list.remove(&cur);
+        self.pos.set(self.list, next, self.pos.index);
+        res
+    }
+
+    // 返回当前元素之后的所有元素，位于空位置时返回整个链表
+    pub fn split_after(&mut self) -> LinkedList<T> {
+        let res = if self.pos.is_ghost(self.list) {
+            let res = std::mem::take(self.list);
+            self.pos = Position::new(self.list, 0);
+            res
+        } else {
+            let last = self.list.tail.borrow().pre.clone().unwrap();
+            let n = self.list.len() - self.pos.index - 1;
+            self.list.split_range(self.pos.next.clone(), last, n)
+        };
+        self.pos.refresh(self.list);
+        res
+    }
+
+    // 返回当前元素之前的所有元素，位于空位置时返回整个链表
+    pub fn split_before(&mut self) -> LinkedList<T> {
+        let res = if self.pos.is_ghost(self.list) {
+            let res = std::mem::take(self.list);
+            self.pos = Position::new(self.list, 0);
+            res
+        } else {
+            let first = self.list.head.borrow().next.clone().unwrap();
+            let n = self.pos.index;
+            self.pos.index = 0;
+            self.list.split_range(first, self.pos.pre.clone(), n)
+        };
+        self.pos.refresh(self.list);
+        res
+    }
+
+    // 把 other 的全部元素插入到当前元素之后，位于空位置时插入到链表头部
+    pub fn splice_after(&mut self, mut other: LinkedList<T>) {
+        if let Some((first, last, n)) = other.take_all() {
+            let pos = if self.pos.is_ghost(self.list) {
+                self.list.head.clone()
+            } else {
+                self.pos.cur.clone()
+            };
+            self.list.link_after(&pos, first, last, n);
+        }
+        self.pos.refresh(self.list);
+    }
+
+    // 把 other 的全部元素插入到当前元素之前，位于空位置时插入到链表尾部
+    pub fn splice_before(&mut self, mut other: LinkedList<T>) {
+        if let Some((first, last, n)) = other.take_all() {
+            let pos = self.pos.pre.clone();
+            self.list.link_after(&pos, first, last, n);
+            if !self.pos.is_ghost(self.list) {
+                self.pos.index += n;
             }
-            return Some(self.cur.clone());
         }
-        None
+        self.pos.refresh(self.list);
+    }
+}
+
+// 按顺序产生元素节点
+pub(super) struct Nodes<T> {
+    cur: Node<T>,
+}
+
+impl<T> Iterator for Nodes<T> {
+    type Item = Node<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.cur.borrow().next.clone()?;
+        next.borrow().val.as_ref()?;
+        self.cur = next.clone();
+        Some(next)
+    }
+}
+
+pub struct Iter<T> {
+    nodes: Nodes<T>,
+}
+
+impl<T: Clone> Iterator for Iter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.nodes.next()?.borrow().val.clone()
     }
 }
 
@@ -167,18 +636,221 @@ mod test {
         link_list.push_back(3);
         link_list.push_back(4);
 
-        let nums = link_list
-            .iter()
-            .map(|x| x.borrow().val.unwrap())
-            .collect::<Vec<i32>>();
+        let nums = link_list.iter().collect::<Vec<i32>>();
 
         assert_eq!(nums, [1, 2, 3, 4]);
 
-        let pos = link_list.find(&10, None);
-        assert_eq!(pos, None);
+        assert!(link_list.find(&10).is_none());
+
+        let pos = link_list.find(&3).unwrap();
+        assert_eq!(pos.index(), Some(2));
+        assert_eq!(pos.current().map(|x| *x), Some(3));
+    }
 
-        let pos = link_list.find(&1, None);
-        assert!(pos.is_some());
-        assert_eq!(pos.unwrap().borrow().val, Some(1));
+    fn to_vec(list: &LinkedList<i32>) -> Vec<i32> {
+        list.iter().collect::<Vec<i32>>()
+    }
+
+    #[test]
+    fn test_erase() {
+        let mut list = LinkedList::new();
+        let a = list.push_back_node(1);
+        let b = list.push_back_node(2);
+        list.push_front(0);
+        list.insert_back(&b, 3);
+        let front = list.front_node().unwrap();
+        list.insert_back(&front, -1);
+        assert_eq!(to_vec(&list), [0, -1, 1, 2, 3]);
+        assert_eq!(list.len(), 5);
+
+        assert_eq!(list.erase(&a), Some(1));
+        assert_eq!(list.erase(&a), None);
+        assert_eq!(list.len(), 4);
+        // 反向也要正确连接
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(list.pop_back(), Some(2));
+        assert_eq!(list.back(), Some(-1));
+        assert_eq!(list.front(), Some(0));
+        assert_eq!(list.pop_front(), Some(0));
+        assert_eq!(list.pop_front(), Some(-1));
+        assert_eq!(list.pop_front(), None);
+        assert!(list.front().is_none());
+        assert!(list.is_empty());
+
+        let a = list.push_back_node(5);
+        list.push_back(6);
+        list.move_to_back(&a);
+        assert_eq!(to_vec(&list), [6, 5]);
+        list.move_to_front(&a);
+        assert_eq!(to_vec(&list), [5, 6]);
+        assert_eq!(list.pop_back(), Some(6));
+        assert_eq!(to_vec(&list), [5]);
+        list.clear();
+        assert!(list.is_empty());
+        assert_eq!(to_vec(&list), []);
+    }
+
+    #[test]
+    fn test_invalid_node() {
+        let mut list = LinkedList::new();
+        let a = list.push_back_node(1);
+        let mut other = LinkedList::new();
+        let b = other.push_back_node(2);
+
+        // 哨兵、已删除的节点和其他链表的节点都不能作为插入位置
+        let (head, tail) = (list.head.clone(), list.tail.clone());
+        assert!(list.insert_back(&tail, 3).is_none());
+        assert!(list.insert_back(&head, 3).is_none());
+        assert!(list.insert_back(&b, 3).is_none());
+        assert_eq!(list.erase(&b), None);
+        assert_eq!(list.erase(&head), None);
+        // 不能把其他链表的节点移过来
+        list.move_to_front(&b);
+        list.move_to_back(&b);
+        assert_eq!(list.len(), 1);
+        assert_eq!(other.len(), 1);
+        assert_eq!(to_vec(&list), [1]);
+
+        assert_eq!(list.erase(&a), Some(1));
+        assert!(list.insert_back(&a, 3).is_none());
+        assert_eq!(list.erase(&b), None);
+        assert_eq!(list.len(), 0);
+        assert_eq!(to_vec(&list), []);
+        assert_eq!(to_vec(&other), [2]);
+
+        // split / splice 转移过来的节点仍可通过游标修改
+        other.extend([3, 4]);
+        let mut cursor = other.cursor_front_mut();
+        let rest = cursor.split_after();
+        cursor.splice_before(rest);
+        let mut cursor = list.cursor_front_mut();
+        cursor.splice_after(other);
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(3));
+        cursor.insert_after(5);
+        cursor.insert_before(6);
+        assert_eq!(to_vec(&list), [6, 4, 5, 2]);
+        assert_eq!(list.pop_back(), Some(2));
+        assert_eq!(list.pop_front(), Some(6));
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn test_drop() {
+        let mut list = LinkedList::new();
+        let nodes = (0..100).map(|x| list.push_back_node(x)).collect::<Vec<_>>();
+        let head = list.head.clone();
+        drop(list);
+        // 链表释放后只剩下这里持有的引用
+        assert_eq!(Rc::strong_count(&head), 1);
+        assert!(nodes.iter().all(|x| Rc::strong_count(x) == 1));
+        assert_eq!(nodes[10].borrow().val(), Some(&10));
+    }
+
+    #[test]
+    fn test_cursor() {
+        let mut list = LinkedList::new();
+        list.extend([1, 2, 3]);
+        let mut cursor = list.cursor_front();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.current().map(|x| *x), Some(1));
+        assert!(cursor.peek_prev().is_none());
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current().map(|x| *x), Some(3));
+        assert!(cursor.peek_next().is_none());
+        // 越过尾部后停在空位置，再往后回到头部
+        cursor.move_next();
+        assert_eq!(cursor.index(), None);
+        assert!(cursor.current().is_none());
+        assert_eq!(cursor.peek_next().map(|x| *x), Some(1));
+        assert_eq!(cursor.peek_prev().map(|x| *x), Some(3));
+        cursor.move_next();
+        assert_eq!(cursor.index(), Some(0));
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(2));
+
+        let empty = LinkedList::<i32>::new();
+        let mut cursor = empty.cursor_back();
+        assert_eq!(cursor.index(), None);
+        cursor.move_next();
+        assert_eq!(cursor.index(), None);
+    }
+
+    #[test]
+    fn test_cursor_mut() {
+        let mut list = LinkedList::new();
+        list.extend([1, 2, 3]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        *cursor.current().unwrap() = 20;
+        cursor.insert_before(15);
+        cursor.insert_after(25);
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.peek_next().map(|x| *x), Some(25));
+        assert_eq!(cursor.remove_current(), Some(20));
+        assert_eq!(cursor.current().map(|x| *x), Some(25));
+        assert_eq!(cursor.as_cursor().index(), Some(2));
+
+        cursor.move_prev();
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.index(), None);
+        cursor.insert_after(0);
+        cursor.insert_before(4);
+        assert_eq!(cursor.remove_current(), None);
+        assert_eq!(to_vec(&list), [0, 1, 15, 25, 3, 4]);
+        assert_eq!(list.len(), 6);
+        assert_eq!(list.back(), Some(4));
+    }
+
+    #[test]
+    fn test_split_splice() {
+        let mut list = LinkedList::new();
+        list.extend(0..6);
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.move_next();
+        let after = cursor.split_after();
+        let before = cursor.split_before();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(
+            (to_vec(&before), to_vec(&after)),
+            (vec![0, 1], vec![3, 4, 5])
+        );
+
+        cursor.splice_after(after);
+        cursor.splice_before(before);
+        assert_eq!(cursor.index(), Some(2));
+        cursor.splice_after(LinkedList::new());
+        assert_eq!(cursor.current().map(|x| *x), Some(2));
+        cursor.move_prev();
+        assert_eq!(cursor.current().map(|x| *x), Some(1));
+        assert_eq!(to_vec(&list), [0, 1, 2, 3, 4, 5]);
+        assert_eq!(list.len(), 6);
+
+        let mut cursor = list.cursor_back_mut();
+        cursor.move_next();
+        let all = cursor.split_after();
+        assert_eq!(cursor.index(), None);
+        assert!(list.is_empty());
+        assert_eq!(to_vec(&all), [0, 1, 2, 3, 4, 5]);
+        assert_eq!(all.len(), 6);
+
+        let mut tail = LinkedList::new();
+        tail.extend([7, 8]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.splice_before(all);
+        cursor.splice_before(tail);
+        assert_eq!(to_vec(&list), [0, 1, 2, 3, 4, 5, 7, 8]);
+        assert_eq!(list.iter().count(), list.len());
+        let mut cursor = list.cursor_back();
+        let mut rev = vec![];
+        while let Some(x) = cursor.current().map(|x| *x) {
+            rev.push(x);
+            cursor.move_prev();
+        }
+        assert_eq!(rev, [8, 7, 5, 4, 3, 2, 1, 0]);
     }
 }
@@ -0,0 +1,227 @@
+use std::borrow::Borrow;
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use super::linked_list::{LinkedList, Node};
+
+// 因容量不足被淘汰时调用，主动 remove 的元素不会触发
+pub type EvictCallback<K, V> = Box<dyn FnMut(K, V)>;
+
+pub(super) fn node_val<K, V>(node: &Node<(K, V)>) -> Ref<'_, V> {
+    Ref::map(RefCell::borrow(node), |x| &x.val().unwrap().1)
+}
+
+pub(super) fn node_val_mut<K, V>(node: &Node<(K, V)>) -> RefMut<'_, V> {
+    RefMut::map(node.borrow_mut(), |x| &mut x.val_mut().unwrap().1)
+}
+
+// 最近最少使用缓存，链表头部为最近使用的元素
+pub struct LruCache<K, V> {
+    map: HashMap<K, Node<(K, V)>>,
+    list: LinkedList<(K, V)>,
+    capacity: usize,
+    on_evict: Option<EvictCallback<K, V>>,
+}
+
+impl<K, V> LruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    pub fn new(capacity: usize) -> Self {
+        LruCache {
+            map: HashMap::with_capacity(capacity),
+            list: LinkedList::new(),
+            capacity,
+            on_evict: None,
+        }
+    }
+
+    pub fn with_evict_callback<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(K, V) + 'static,
+    {
+        self.on_evict = Some(Box::new(f));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    // 访问元素并把它标记为最近使用
+    pub fn get<Q>(&mut self, key: &Q) -> Option<Ref<'_, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node = self.map.get(key)?;
+        self.list.move_to_front(node);
+        Some(node_val(node))
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<RefMut<'_, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node = self.map.get(key)?;
+        self.list.move_to_front(node);
+        Some(node_val_mut(node))
+    }
+
+    // 访问元素但不改变使用顺序
+    pub fn peek<Q>(&self, key: &Q) -> Option<Ref<'_, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get(key).map(node_val)
+    }
+
+    // 最久未使用的元素
+    pub fn peek_lru(&self) -> Option<(K, Ref<'_, V>)> {
+        let node = self.list.back_node()?;
+        let key = RefCell::borrow(&node).val().unwrap().0.clone();
+        self.peek(&key).map(|v| (key, v))
+    }
+
+    // 键已存在时更新值并返回旧值
+    pub fn put(&mut self, key: K, val: V) -> Option<V> {
+        if let Some(node) = self.map.get(&key) {
+            self.list.move_to_front(node);
+            return Some(std::mem::replace(&mut *node_val_mut(node), val));
+        }
+        if self.capacity == 0 {
+            self.evict(key, val);
+            return None;
+        }
+        if self.map.len() >= self.capacity {
+            self.pop_evict();
+        }
+        let node = self.list.push_front_node((key.clone(), val));
+        self.map.insert(key, node);
+        None
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node = self.map.remove(key)?;
+        self.list.erase(&node).map(|x| x.1)
+    }
+
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        let (key, val) = self.list.pop_back()?;
+        self.map.remove(&key);
+        Some((key, val))
+    }
+
+    fn evict(&mut self, key: K, val: V) {
+        if let Some(f) = self.on_evict.as_mut() {
+            f(key, val);
+        }
+    }
+
+    fn pop_evict(&mut self) {
+        if let Some((key, val)) = self.pop_lru() {
+            self.evict(key, val);
+        }
+    }
+
+    // 缩小容量时按最久未使用的顺序淘汰多出的元素
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.map.len() > capacity {
+            self.pop_evict();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.list.clear();
+    }
+
+    // 从最近使用到最久未使用
+    pub fn keys(&self) -> impl Iterator<Item = K> + '_ {
+        self.list
+            .nodes()
+            .map(|x| RefCell::borrow(&x).val().unwrap().0.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test1() {
+        let mut cache = LruCache::new(2);
+        assert_eq!(cache.put(1, "a"), None);
+        cache.put(2, "b");
+        assert_eq!(cache.get(&1).map(|x| *x), Some("a"));
+        // 2 最久未使用，被淘汰
+        cache.put(3, "c");
+        assert!(!cache.contains(&2));
+        assert_eq!(cache.keys().collect::<Vec<i32>>(), [3, 1]);
+
+        assert_eq!(cache.peek(&1).map(|x| *x), Some("a"));
+        assert_eq!(cache.peek_lru().map(|x| x.0), Some(1));
+        assert_eq!(cache.put(1, "A"), Some("a"));
+        assert_eq!(cache.peek_lru().map(|x| x.0), Some(3));
+        *cache.get_mut(&3).unwrap() = "C";
+        assert_eq!(cache.remove(&3), Some("C"));
+        assert_eq!(cache.remove(&3), None);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.pop_lru(), Some((1, "A")));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_evict() {
+        let evicted = Rc::new(RefCell::new(vec![]));
+        let log = evicted.clone();
+        let mut cache =
+            LruCache::new(3).with_evict_callback(move |k, v| log.borrow_mut().push((k, v)));
+        for i in 0..5 {
+            cache.put(i.to_string(), i);
+        }
+        cache.get("2");
+        cache.set_capacity(1);
+        assert_eq!(cache.keys().collect::<Vec<String>>(), ["2"]);
+        cache.set_capacity(0);
+        cache.put("x".to_string(), 10);
+        assert_eq!(cache.capacity(), 0);
+        assert!(cache.is_empty());
+        assert_eq!(
+            evicted.take(),
+            [
+                ("0".to_string(), 0),
+                ("1".to_string(), 1),
+                ("3".to_string(), 3),
+                ("4".to_string(), 4),
+                ("2".to_string(), 2),
+                ("x".to_string(), 10)
+            ]
+        );
+    }
+}
@@ -0,0 +1,306 @@
+use super::priority_queue::{BoxedCompare, Compare, MinFirst};
+
+// 最小-最大堆：偶数层为小根层，奇数层为大根层
+// 按 cmp 排在最前的为最小元素，排在最后的为最大元素
+pub struct MinMaxHeap<T, C = BoxedCompare<T>> {
+    que: Vec<T>,
+    cmp: C,
+    // 有上限时插入溢出会淘汰最大元素
+    bound: Option<usize>,
+}
+
+impl<T> MinMaxHeap<T> {
+    pub fn new<F>(nums: Option<Vec<T>>, cmp: F) -> Self
+    where
+        F: Fn(&T, &T) -> bool + 'static,
+    {
+        let cmp_func: BoxedCompare<T> = Box::new(cmp);
+        Self::with_compare(nums, cmp_func)
+    }
+}
+
+impl<T: PartialOrd> MinMaxHeap<T, MinFirst> {
+    pub fn new_ord(nums: Option<Vec<T>>) -> Self {
+        Self::with_compare(nums, MinFirst)
+    }
+}
+
+fn is_min_level(idx: usize) -> bool {
+    (idx + 1).ilog2().is_multiple_of(2)
+}
+
+impl<T, C> MinMaxHeap<T, C>
+where
+    C: Compare<T>,
+{
+    pub fn with_compare(nums: Option<Vec<T>>, cmp: C) -> Self {
+        let mut r = MinMaxHeap {
+            que: nums.unwrap_or_default(),
+            cmp,
+            bound: None,
+        };
+        for i in (0..r.que.len() / 2).rev() {
+            r.trickle_down(i);
+        }
+        r
+    }
+
+    // 限制元素个数，多出的最大元素会被丢弃
+    pub fn bounded(mut self, bound: usize) -> Self {
+        self.bound = Some(bound);
+        while self.que.len() > bound {
+            self.pop_max();
+        }
+        self
+    }
+
+    pub fn bound(&self) -> Option<usize> {
+        self.bound
+    }
+
+    pub fn len(&self) -> usize {
+        self.que.len()
+    }
+
+    pub fn empty(&self) -> bool {
+        self.que.is_empty()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.que.is_empty()
+    }
+
+    // max 为 true 时按大根层比较
+    fn better(&self, i: usize, j: usize, max: bool) -> bool {
+        if max {
+            self.cmp.before(&self.que[j], &self.que[i])
+        } else {
+            self.cmp.before(&self.que[i], &self.que[j])
+        }
+    }
+
+    // 沿祖父节点向上调整
+    fn push_up_by(&mut self, mut idx: usize, max: bool) {
+        while idx > 2 {
+            let grand = ((idx - 1) / 2 - 1) / 2;
+            if !self.better(idx, grand, max) {
+                break;
+            }
+            self.que.swap(idx, grand);
+            idx = grand;
+        }
+    }
+
+    fn push_up(&mut self, idx: usize) {
+        if idx == 0 {
+            return;
+        }
+        let max = !is_min_level(idx);
+        let parent = (idx - 1) / 2;
+        // 父节点在相反的层，先和父节点比较决定沿哪种层向上
+        if self.better(idx, parent, !max) {
+            self.que.swap(idx, parent);
+            self.push_up_by(parent, !max);
+        } else {
+            self.push_up_by(idx, max);
+        }
+    }
+
+    fn trickle_down(&mut self, mut idx: usize) {
+        let max = !is_min_level(idx);
+        let len = self.que.len();
+        loop {
+            let child = idx * 2 + 1;
+            if child >= len {
+                return;
+            }
+            // 在孩子和孙子中找出最优的一个
+            let mut m = child;
+            let candidates = [child + 1, child * 2 + 1, child * 2 + 2];
+            let grand_children = (child + 1) * 2 + 1..((child + 1) * 2 + 3);
+            for c in candidates.into_iter().chain(grand_children) {
+                if c < len && self.better(c, m, max) {
+                    m = c;
+                }
+            }
+            if !self.better(m, idx, max) {
+                return;
+            }
+            self.que.swap(m, idx);
+            if m <= child + 1 {
+                return;
+            }
+            // m 是孙子，换下来的元素可能比 m 的父节点更差
+            let parent = (m - 1) / 2;
+            if self.better(parent, m, max) {
+                self.que.swap(m, parent);
+            }
+            idx = m;
+        }
+    }
+
+    fn max_index(&self) -> Option<usize> {
+        match self.que.len() {
+            0 => None,
+            1 => Some(0),
+            2 => Some(1),
+            _ => Some(if self.better(1, 2, true) { 1 } else { 2 }),
+        }
+    }
+
+    fn remove_at(&mut self, idx: usize) -> T {
+        let item = self.que.swap_remove(idx);
+        if idx < self.que.len() {
+            self.trickle_down(idx);
+        }
+        item
+    }
+
+    // 有上限且已满时返回被淘汰的元素，可能是 item 本身
+    pub fn push(&mut self, item: T) -> Option<T> {
+        if let Some(bound) = self.bound {
+            if self.que.len() >= bound {
+                let keep = self
+                    .max_index()
+                    .is_some_and(|m| self.cmp.before(&item, &self.que[m]));
+                if !keep {
+                    return Some(item);
+                }
+                self.que.push(item);
+                self.push_up(self.que.len() - 1);
+                return self.pop_max();
+            }
+        }
+        self.que.push(item);
+        self.push_up(self.que.len() - 1);
+        None
+    }
+
+    pub fn peek_min(&self) -> Option<&T> {
+        self.que.first()
+    }
+
+    pub fn peek_max(&self) -> Option<&T> {
+        self.max_index().map(|m| &self.que[m])
+    }
+
+    pub fn pop_min(&mut self) -> Option<T> {
+        if self.que.is_empty() {
+            return None;
+        }
+        Some(self.remove_at(0))
+    }
+
+    pub fn pop_max(&mut self) -> Option<T> {
+        let m = self.max_index()?;
+        Some(self.remove_at(m))
+    }
+
+    pub fn clear(&mut self) {
+        self.que.clear();
+    }
+
+    // 按堆内部顺序遍历
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.que.iter()
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.que
+    }
+
+    // 从最小到最大
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut res = Vec::with_capacity(self.len());
+        while let Some(x) = self.pop_min() {
+            res.push(x);
+        }
+        res
+    }
+}
+
+impl<T, C> FromIterator<T> for MinMaxHeap<T, C>
+where
+    C: Compare<T> + Default,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::with_compare(Some(iter.into_iter().collect()), C::default())
+    }
+}
+
+// 有上限时被淘汰的元素直接丢弃
+impl<T, C: Compare<T>> Extend<T> for MinMaxHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn test1() {
+        let mut heap = MinMaxHeap::new_ord(Some(vec![5, 3, 8, 1, 9, 2]));
+        assert_eq!(heap.peek_min(), Some(&1));
+        assert_eq!(heap.peek_max(), Some(&9));
+        assert_eq!(heap.pop_max(), Some(9));
+        assert_eq!(heap.pop_min(), Some(1));
+        assert_eq!(heap.pop_max(), Some(8));
+        heap.push(0);
+        assert_eq!(heap.len(), 4);
+        assert_eq!(heap.into_sorted_vec(), [0, 2, 3, 5]);
+
+        let mut heap = MinMaxHeap::new(None, |a: &i32, b: &i32| a > b);
+        heap.extend([1, 2, 3]);
+        assert_eq!(heap.pop_min(), Some(3));
+        assert_eq!(heap.pop_max(), Some(1));
+        assert_eq!(heap.pop_max(), Some(2));
+        assert_eq!(heap.pop_max(), None);
+        assert_eq!(heap.peek_min(), None);
+    }
+
+    #[test]
+    fn test_bounded() {
+        let mut heap = MinMaxHeap::new_ord(Some(vec![4, 1, 7, 3])).bounded(3);
+        assert_eq!(heap.len(), 3);
+        assert_eq!(heap.peek_max(), Some(&4));
+        assert_eq!(heap.push(2), Some(4));
+        assert_eq!(heap.push(5), Some(5));
+        assert_eq!(heap.push(3), Some(3));
+        assert_eq!(heap.into_sorted_vec(), [1, 2, 3]);
+
+        let mut heap = MinMaxHeap::new_ord(None).bounded(0);
+        assert_eq!(heap.push(1), Some(1));
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = thread_rng();
+        for n in [1, 2, 3, 7, 100, 1000] {
+            let nums = (0..n).map(|_| rng.gen_range(0..100)).collect::<Vec<i32>>();
+            let mut heap = MinMaxHeap::new_ord(Some(nums.clone()));
+            let mut expect = nums;
+            expect.sort();
+            let mut expect = std::collections::VecDeque::from(expect);
+            for _ in 0..2 * n {
+                if rng.gen_bool(0.3) {
+                    let x = rng.gen_range(0..100);
+                    heap.push(x);
+                    let pos = expect.partition_point(|&y| y < x);
+                    expect.insert(pos, x);
+                } else if rng.gen_bool(0.5) {
+                    assert_eq!(heap.pop_min(), expect.pop_front());
+                } else {
+                    assert_eq!(heap.pop_max(), expect.pop_back());
+                }
+                assert_eq!(heap.peek_min(), expect.front());
+                assert_eq!(heap.peek_max(), expect.back());
+            }
+        }
+    }
+}
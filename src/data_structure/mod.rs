@@ -1,5 +1,14 @@
+pub mod arena_linked_list;
+pub mod fibonacci_heap;
+pub mod indexed_priority_queue;
+pub mod leftist_heap;
+pub mod lfu_cache;
 pub mod linked_list;
+pub mod lru_cache;
+pub mod min_max_heap;
+pub mod pairing_heap;
 pub mod priority_queue;
+pub mod raw_linked_list;
 pub mod skip_list;
 pub mod skip_list_map;
 pub mod tree_array;
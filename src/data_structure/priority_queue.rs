@@ -1,21 +1,141 @@
-struct PriorityQueue<T> {
+use std::cell::RefCell;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+
+// before(a, b) 为 true 表示 a 应当比 b 先出队
+pub trait Compare<T> {
+    fn before(&self, a: &T, b: &T) -> bool;
+}
+
+impl<T, F> Compare<T> for F
+where
+    F: Fn(&T, &T) -> bool,
+{
+    fn before(&self, a: &T, b: &T) -> bool {
+        self(a, b)
+    }
+}
+
+// 小根堆
+#[derive(Clone, Copy, Default, Debug)]
+pub struct MinFirst;
+
+impl<T: PartialOrd> Compare<T> for MinFirst {
+    fn before(&self, a: &T, b: &T) -> bool {
+        a < b
+    }
+}
+
+// 大根堆
+#[derive(Clone, Copy, Default, Debug)]
+pub struct MaxFirst;
+
+impl<T: PartialOrd> Compare<T> for MaxFirst {
+    fn before(&self, a: &T, b: &T) -> bool {
+        a > b
+    }
+}
+
+pub type BoxedCompare<T> = Box<dyn Fn(&T, &T) -> bool>;
+
+// 可合并堆的身份标记，句柄记录发出它的堆的标记
+// meld 时把被并入堆的标记指向合并后的堆，沿指向找到的根标记相同即属于同一个堆（并查集）
+#[derive(Default)]
+pub(super) struct HeapToken {
+    parent: RefCell<Option<Rc<HeapToken>>>,
+}
+
+impl HeapToken {
+    pub(super) fn new() -> Rc<Self> {
+        Rc::new(HeapToken::default())
+    }
+
+    // 返回根标记并压缩路径
+    pub(super) fn find(this: &Rc<Self>) -> Rc<Self> {
+        let mut root = this.clone();
+        loop {
+            let parent = root.parent.borrow().clone();
+            match parent {
+                Some(p) => root = p,
+                None => break,
+            }
+        }
+        let mut cur = this.clone();
+        while !Rc::ptr_eq(&cur, &root) {
+            let next = cur.parent.replace(Some(root.clone())).unwrap();
+            cur = next;
+        }
+        root
+    }
+
+    // this 必须是根标记，之后 this 发出的句柄都属于 root 所在的堆
+    pub(super) fn union(this: &Rc<Self>, root: &Rc<Self>) {
+        this.parent.replace(Some(root.clone()));
+    }
+}
+
+// 标记链可能很长，非递归释放
+impl Drop for HeapToken {
+    fn drop(&mut self) {
+        let mut cur = self.parent.get_mut().take();
+        while let Some(p) = cur {
+            cur = match Rc::try_unwrap(p) {
+                Ok(mut x) => x.parent.get_mut().take(),
+                Err(_) => None,
+            };
+        }
+    }
+}
+
+pub struct PriorityQueue<T, C = BoxedCompare<T>> {
     que: Vec<T>,
-    cmp: Box<dyn Fn(&T, &T) -> bool>,
+    cmp: C,
+}
+
+impl<T> PriorityQueue<T> {
+    pub fn new<F>(nums: Option<Vec<T>>, cmp: F) -> Self
+    where
+        F: Fn(&T, &T) -> bool + 'static,
+    {
+        let cmp_func: BoxedCompare<T> = Box::new(cmp);
+        Self::with_compare(nums, cmp_func)
+    }
+}
+
+impl<T: PartialOrd> PriorityQueue<T, MinFirst> {
+    pub fn new_min(nums: Option<Vec<T>>) -> Self {
+        Self::with_compare(nums, MinFirst)
+    }
+}
+
+impl<T: PartialOrd> PriorityQueue<T, MaxFirst> {
+    pub fn new_max(nums: Option<Vec<T>>) -> Self {
+        Self::with_compare(nums, MaxFirst)
+    }
 }
 
-impl<T> PriorityQueue<T>
+impl<T, C> PriorityQueue<T, C>
 where
-    T: PartialOrd + Clone,
+    C: Compare<T>,
 {
+    pub fn with_compare(nums: Option<Vec<T>>, cmp: C) -> Self {
+        let mut r = PriorityQueue {
+            que: nums.unwrap_or_default(),
+            cmp,
+        };
+        r.rebuild();
+        r
+    }
+
     pub fn heapify(&mut self, start: usize, end: usize) {
         let mut far = start;
         let mut child = far * 2 + 1;
         while child <= end {
-            if child < end && (self.cmp)(&self.que[child + 1], &self.que[child]) {
+            if child < end && self.cmp.before(&self.que[child + 1], &self.que[child]) {
                 child += 1;
             }
 
-            if (self.cmp)(&self.que[far], &self.que[child]) {
+            if !self.cmp.before(&self.que[child], &self.que[far]) {
                 break;
             }
             self.que.swap(far, child);
@@ -24,26 +144,26 @@ where
         }
     }
 
-    pub fn new<F>(nums: Option<Vec<T>>, cmp: F) -> Self
-    where
-        F: Fn(&T, &T) -> bool + 'static,
-    {
-        let cmp_func: Box<dyn Fn(&T, &T) -> bool> = Box::new(cmp);
+    fn sift_down(&mut self, start: usize) {
+        if let Some(end) = self.len().checked_sub(1) {
+            self.heapify(start, end);
+        }
+    }
 
-        if let Some(nums) = nums {
-            let mut r = PriorityQueue {
-                que: nums,
-                cmp: cmp_func,
-            };
-            for i in (0..=r.que.len() / 2 - 1).rev() {
-                r.heapify(i, r.que.len() - 1);
+    fn sift_up(&mut self, mut child: usize) {
+        while child > 0 {
+            let far = (child - 1) / 2;
+            if !self.cmp.before(&self.que[child], &self.que[far]) {
+                break;
             }
-            return r;
+            self.que.swap(far, child);
+            child = far;
         }
+    }
 
-        PriorityQueue {
-            que: vec![],
-            cmp: cmp_func,
+    fn rebuild(&mut self) {
+        for i in (0..self.len() / 2).rev() {
+            self.sift_down(i);
         }
     }
 
@@ -55,69 +175,229 @@ where
         self.que.is_empty()
     }
 
-    pub fn pop(&mut self) -> T {
-        if self.empty() {
-            panic!("queue is empty");
-        }
-        let mut far = 0;
-        let mut child = far * 2 + 1;
-        while child < self.len() {
-            if child < self.len() - 1 && (self.cmp)(&self.que[child + 1], &self.que[child]) {
-                child += 1;
-            }
-            self.que.swap(far, child);
+    pub fn is_empty(&self) -> bool {
+        self.que.is_empty()
+    }
 
-            far = child;
-            child = far * 2 + 1;
+    pub fn pop(&mut self) -> Option<T> {
+        if self.empty() {
+            return None;
         }
-        let l = self.len();
-        self.que.swap(far, l - 1);
-        self.que.pop().unwrap()
+        let res = self.que.swap_remove(0);
+        self.sift_down(0);
+        Some(res)
     }
 
     pub fn push(&mut self, item: T) {
         self.que.push(item);
-        let l = self.len();
-        let mut far = l / 2 - 1;
-        while far >= 0 {
-            let mut child = far * 2 + 1;
-            if child < l - 1 && (self.cmp)(&self.que[child + 1], &self.que[child]) {
-                child += 1;
-            }
-            if (self.cmp)(&self.que[far], &self.que[child]) {
-                break;
-            }
-            self.que.swap(far, child);
-            if far == 0 {
-                break;
-            }
-            far = (far - 1) / 2;
+        self.sift_up(self.len() - 1);
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.que.first()
+    }
+
+    // 返回堆顶的可变引用，PeekMut 析构时重新调整堆
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, C>> {
+        if self.empty() {
+            return None;
+        }
+        Some(PeekMut { queue: self })
+    }
+
+    pub fn clear(&mut self) {
+        self.que.clear();
+    }
+
+    // 按堆中的存储顺序遍历，不保证优先级顺序
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.que.iter()
+    }
+
+    // 按堆中的存储顺序取出所有元素，队列被清空
+    pub fn drain(&mut self) -> std::vec::Drain<'_, T> {
+        self.que.drain(..)
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.que
+    }
+
+    // 按出队顺序返回所有元素
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.into_iter().collect()
+    }
+}
+
+pub struct PeekMut<'a, T, C: Compare<T>> {
+    queue: &'a mut PriorityQueue<T, C>,
+}
+
+impl<T, C: Compare<T>> PeekMut<'_, T, C> {
+    pub fn pop(this: Self) -> T {
+        let mut this = std::mem::ManuallyDrop::new(this);
+        this.queue.pop().unwrap()
+    }
+}
+
+impl<T, C: Compare<T>> Deref for PeekMut<'_, T, C> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.queue.que[0]
+    }
+}
+
+impl<T, C: Compare<T>> DerefMut for PeekMut<'_, T, C> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.queue.que[0]
+    }
+}
+
+impl<T, C: Compare<T>> Drop for PeekMut<'_, T, C> {
+    fn drop(&mut self) {
+        self.queue.sift_down(0);
+    }
+}
+
+impl<T, C> FromIterator<T> for PriorityQueue<T, C>
+where
+    C: Compare<T> + Default,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::with_compare(Some(iter.into_iter().collect()), C::default())
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for PriorityQueue<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
         }
     }
 }
 
+// 按出队顺序产生元素
+pub struct IntoIter<T, C: Compare<T>> {
+    queue: PriorityQueue<T, C>,
+}
+
+impl<T, C: Compare<T>> Iterator for IntoIter<T, C> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        self.queue.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.queue.len(), Some(self.queue.len()))
+    }
+}
+
+impl<T, C: Compare<T>> IntoIterator for PriorityQueue<T, C> {
+    type Item = T;
+    type IntoIter = IntoIter<T, C>;
+    fn into_iter(self) -> IntoIter<T, C> {
+        IntoIter { queue: self }
+    }
+}
+
+impl<'a, T, C: Compare<T>> IntoIterator for &'a PriorityQueue<T, C> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::PriorityQueue;
+    use super::*;
+    use rand::{thread_rng, Rng};
     #[test]
     fn test_min() {
         let nums = vec![4, 3, 1, 2];
         let mut que = PriorityQueue::new(Some(nums), |x, y| x < y);
-        assert_eq!(que.pop(), 1);
+        assert_eq!(que.pop(), Some(1));
         que.push(-2);
-        assert_eq!(que.pop(), -2);
-        assert_eq!(que.pop(), 2);
-        assert_eq!(que.pop(), 3);
+        assert_eq!(que.pop(), Some(-2));
+        assert_eq!(que.pop(), Some(2));
+        assert_eq!(que.pop(), Some(3));
     }
 
     #[test]
     fn test_max() {
         let nums = vec![4, 3, 1, 2];
         let mut que = PriorityQueue::new(Some(nums), |x, y| x > y);
-        assert_eq!(que.pop(), 4);
+        assert_eq!(que.pop(), Some(4));
         que.push(100);
-        assert_eq!(que.pop(), 100);
-        assert_eq!(que.pop(), 3);
-        assert_eq!(que.pop(), 2);
+        assert_eq!(que.pop(), Some(100));
+        assert_eq!(que.pop(), Some(3));
+        assert_eq!(que.pop(), Some(2));
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = thread_rng();
+        let mut que = PriorityQueue::new(None, |x: &i32, y: &i32| x < y);
+        let mut nums = vec![];
+        for _ in 0..1000 {
+            let x = rng.gen_range(-100..100);
+            que.push(x);
+            nums.push(x);
+        }
+        nums.sort();
+        assert_eq!(que.peek(), Some(&nums[0]));
+        let res = (0..1000).map(|_| que.pop().unwrap()).collect::<Vec<i32>>();
+        assert_eq!(res, nums);
+        assert!(que.empty());
+    }
+
+    #[test]
+    fn test_empty() {
+        let mut que = PriorityQueue::new(Some(vec![]), |x: &i32, y: &i32| x < y);
+        assert_eq!(que.pop(), None);
+        assert_eq!(que.peek(), None);
+        assert!(que.peek_mut().is_none());
+
+        let mut que = PriorityQueue::new_max(Some(vec![7]));
+        assert_eq!(que.pop(), Some(7));
+        assert_eq!(que.pop(), None);
+    }
+
+    #[test]
+    fn test_min_max() {
+        let que = PriorityQueue::new_min(Some(vec![5, 1, 4, 2, 3]));
+        assert_eq!(que.into_sorted_vec(), [1, 2, 3, 4, 5]);
+
+        let mut que: PriorityQueue<i32, MaxFirst> = (0..10).collect();
+        que.extend([20, -1]);
+        assert_eq!(que.len(), 12);
+        assert_eq!(que.peek(), Some(&20));
+        let res = que.into_iter().take(3).collect::<Vec<i32>>();
+        assert_eq!(res, [20, 9, 8]);
+    }
+
+    #[test]
+    fn test_peek_mut() {
+        let mut que = PriorityQueue::new_max(Some(vec![3, 8, 5]));
+        if let Some(mut top) = que.peek_mut() {
+            *top = 1;
+        }
+        assert_eq!(que.peek(), Some(&5));
+
+        let top = que.peek_mut().map(PeekMut::pop);
+        assert_eq!(top, Some(5));
+        assert_eq!(que.into_sorted_vec(), [3, 1]);
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut que = PriorityQueue::new_min(Some(vec![3, 1, 2]));
+        assert_eq!((&que).into_iter().count(), 3);
+        let mut all = que.drain().collect::<Vec<i32>>();
+        all.sort();
+        assert_eq!(all, [1, 2, 3]);
+        assert!(que.is_empty());
+        que.push(4);
+        assert_eq!(que.into_vec(), [4]);
     }
 }
@@ -0,0 +1,705 @@
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+type Link<T> = Option<NonNull<RawNode<T>>>;
+
+struct RawNode<T> {
+    val: T,
+    next: Link<T>,
+    pre: Link<T>,
+}
+
+impl<T> RawNode<T> {
+    fn new_ptr(val: T) -> NonNull<RawNode<T>> {
+        let boxed = Box::new(RawNode {
+            val,
+            next: None,
+            pre: None,
+        });
+        NonNull::from(Box::leak(boxed))
+    }
+}
+
+// 基于裸指针的双向链表，拼接、拆分都是 O(1)
+pub struct RawLinkedList<T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    marker: PhantomData<Box<RawNode<T>>>,
+}
+
+unsafe impl<T: Send> Send for RawLinkedList<T> {}
+unsafe impl<T: Sync> Sync for RawLinkedList<T> {}
+
+impl<T> RawLinkedList<T> {
+    pub fn new() -> Self {
+        RawLinkedList {
+            head: None,
+            tail: None,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push_front(&mut self, val: T) {
+        let node = RawNode::new_ptr(val);
+        unsafe {
+            (*node.as_ptr()).next = self.head;
+            match self.head {
+                Some(head) => (*head.as_ptr()).pre = Some(node),
+                None => self.tail = Some(node),
+            }
+        }
+        self.head = Some(node);
+        self.len += 1;
+    }
+
+    pub fn push_back(&mut self, val: T) {
+        let node = RawNode::new_ptr(val);
+        unsafe {
+            (*node.as_ptr()).pre = self.tail;
+            match self.tail {
+                Some(tail) => (*tail.as_ptr()).next = Some(node),
+                None => self.head = Some(node),
+            }
+        }
+        self.tail = Some(node);
+        self.len += 1;
+    }
+
+    // 把 node 从链表中摘下并释放，返回它的值
+    unsafe fn unlink(&mut self, node: NonNull<RawNode<T>>) -> T {
+        let boxed = Box::from_raw(node.as_ptr());
+        match boxed.pre {
+            Some(pre) => (*pre.as_ptr()).next = boxed.next,
+            None => self.head = boxed.next,
+        }
+        match boxed.next {
+            Some(next) => (*next.as_ptr()).pre = boxed.pre,
+            None => self.tail = boxed.pre,
+        }
+        self.len -= 1;
+        boxed.val
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.head.map(|node| unsafe { self.unlink(node) })
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.tail.map(|node| unsafe { self.unlink(node) })
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.head.map(|node| unsafe { &(*node.as_ptr()).val })
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.tail.map(|node| unsafe { &(*node.as_ptr()).val })
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.head.map(|node| unsafe { &mut (*node.as_ptr()).val })
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.tail.map(|node| unsafe { &mut (*node.as_ptr()).val })
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    // 把 other 的全部元素移到尾部，other 变为空
+    pub fn append(&mut self, other: &mut Self) {
+        let Some(other_head) = other.head.take() else {
+            return;
+        };
+        match self.tail {
+            Some(tail) => unsafe {
+                (*tail.as_ptr()).next = Some(other_head);
+                (*other_head.as_ptr()).pre = Some(tail);
+            },
+            None => self.head = Some(other_head),
+        }
+        self.tail = other.tail.take();
+        self.len += std::mem::take(&mut other.len);
+    }
+
+    // 拆成 [0, at) 和 [at, len)，返回后一段
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.len, "split index out of bounds");
+        if at == self.len {
+            return Self::new();
+        }
+        let mut cursor = self.cursor_at(at);
+        let mut res = cursor.split_before();
+        std::mem::swap(self, &mut res);
+        res
+    }
+
+    pub fn reverse(&mut self) {
+        let mut cur = self.head;
+        while let Some(node) = cur {
+            unsafe {
+                let node = &mut *node.as_ptr();
+                std::mem::swap(&mut node.next, &mut node.pre);
+                cur = node.pre;
+            }
+        }
+        std::mem::swap(&mut self.head, &mut self.tail);
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            cur: self.head,
+            index: 0,
+            list: self,
+        }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            cur: self.tail,
+            index: self.len.saturating_sub(1),
+            list: self,
+        }
+    }
+
+    // 从离 at 较近的一端移动过去
+    fn cursor_at(&mut self, at: usize) -> CursorMut<'_, T> {
+        if at <= self.len / 2 {
+            let mut cursor = self.cursor_front_mut();
+            for _ in 0..at {
+                cursor.move_next();
+            }
+            cursor
+        } else {
+            let steps = self.len - 1 - at;
+            let mut cursor = self.cursor_back_mut();
+            for _ in 0..steps {
+                cursor.move_prev();
+            }
+            cursor
+        }
+    }
+}
+
+impl<T> Default for RawLinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for RawLinkedList<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T> Extend<T> for RawLinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push_back(item);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for RawLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+pub struct Iter<'a, T> {
+    head: Link<T>,
+    tail: Link<T>,
+    // 剩余元素个数，两端相遇时为 0
+    len: usize,
+    marker: PhantomData<&'a RawNode<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.head.map(|node| unsafe {
+            let node = &*node.as_ptr();
+            self.len -= 1;
+            self.head = node.next;
+            &node.val
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.tail.map(|node| unsafe {
+            let node = &*node.as_ptr();
+            self.len -= 1;
+            self.tail = node.pre;
+            &node.val
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IterMut<'a, T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    marker: PhantomData<&'a mut RawNode<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.head.map(|node| unsafe {
+            let node = &mut *node.as_ptr();
+            self.len -= 1;
+            self.head = node.next;
+            &mut node.val
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.tail.map(|node| unsafe {
+            let node = &mut *node.as_ptr();
+            self.len -= 1;
+            self.tail = node.pre;
+            &mut node.val
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+pub struct IntoIter<T>(RawLinkedList<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for RawLinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RawLinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut RawLinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+// 可修改链表的游标，cur 为 None 时位于首尾之间的空位置
+pub struct CursorMut<'a, T> {
+    list: &'a mut RawLinkedList<T>,
+    cur: Link<T>,
+    index: usize,
+}
+
+impl<T> CursorMut<'_, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.cur.map(|_| self.index)
+    }
+
+    pub fn move_next(&mut self) {
+        match self.cur {
+            Some(node) => unsafe {
+                self.cur = (*node.as_ptr()).next;
+                self.index += 1;
+            },
+            None => {
+                self.cur = self.list.head;
+                self.index = 0;
+            }
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        match self.cur {
+            Some(node) => unsafe {
+                self.cur = (*node.as_ptr()).pre;
+                self.index = self.index.checked_sub(1).unwrap_or(self.list.len);
+            },
+            None => {
+                self.cur = self.list.tail;
+                self.index = self.list.len.saturating_sub(1);
+            }
+        }
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.cur.map(|node| unsafe { &mut (*node.as_ptr()).val })
+    }
+
+    fn next_node(&self) -> Link<T> {
+        match self.cur {
+            Some(node) => unsafe { (*node.as_ptr()).next },
+            None => self.list.head,
+        }
+    }
+
+    fn pre_node(&self) -> Link<T> {
+        match self.cur {
+            Some(node) => unsafe { (*node.as_ptr()).pre },
+            None => self.list.tail,
+        }
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        self.next_node()
+            .map(|node| unsafe { &mut (*node.as_ptr()).val })
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        self.pre_node()
+            .map(|node| unsafe { &mut (*node.as_ptr()).val })
+    }
+
+    // 把 first..=last 这一段（共 n 个节点）接到 pre 与 next 之间
+    unsafe fn link_between(
+        &mut self,
+        pre: Link<T>,
+        next: Link<T>,
+        first: NonNull<RawNode<T>>,
+        last: NonNull<RawNode<T>>,
+        n: usize,
+    ) {
+        (*first.as_ptr()).pre = pre;
+        (*last.as_ptr()).next = next;
+        match pre {
+            Some(pre) => (*pre.as_ptr()).next = Some(first),
+            None => self.list.head = Some(first),
+        }
+        match next {
+            Some(next) => (*next.as_ptr()).pre = Some(last),
+            None => self.list.tail = Some(last),
+        }
+        self.list.len += n;
+    }
+
+    // 位于空位置时插入到链表头部
+    pub fn insert_after(&mut self, item: T) {
+        let node = RawNode::new_ptr(item);
+        unsafe { self.link_between(self.cur, self.next_node(), node, node, 1) };
+        if self.cur.is_none() {
+            self.index = self.list.len;
+        }
+    }
+
+    // 位于空位置时插入到链表尾部
+    pub fn insert_before(&mut self, item: T) {
+        let node = RawNode::new_ptr(item);
+        unsafe { self.link_between(self.pre_node(), self.cur, node, node, 1) };
+        self.index += 1;
+    }
+
+    // 删除当前元素，游标移到下一个元素
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.cur?;
+        self.cur = unsafe { (*node.as_ptr()).next };
+        let val = unsafe { self.list.unlink(node) };
+        if self.cur.is_none() {
+            self.index = self.list.len;
+        }
+        Some(val)
+    }
+
+    // 返回当前元素之后的所有元素，位于空位置时返回整个链表
+    pub fn split_after(&mut self) -> RawLinkedList<T> {
+        let Some(node) = self.cur else {
+            return std::mem::take(self.list);
+        };
+        let mut res = RawLinkedList::new();
+        unsafe {
+            let Some(next) = (*node.as_ptr()).next.take() else {
+                return res;
+            };
+            (*next.as_ptr()).pre = None;
+            res.head = Some(next);
+            res.tail = self.list.tail.replace(node);
+        }
+        res.len = self.list.len - self.index - 1;
+        self.list.len = self.index + 1;
+        res
+    }
+
+    // 返回当前元素之前的所有元素，位于空位置时返回整个链表
+    pub fn split_before(&mut self) -> RawLinkedList<T> {
+        let Some(node) = self.cur else {
+            let res = std::mem::take(self.list);
+            self.index = 0;
+            return res;
+        };
+        let mut res = RawLinkedList::new();
+        unsafe {
+            let Some(pre) = (*node.as_ptr()).pre.take() else {
+                return res;
+            };
+            (*pre.as_ptr()).next = None;
+            res.tail = Some(pre);
+            res.head = self.list.head.replace(node);
+        }
+        res.len = self.index;
+        self.list.len -= self.index;
+        self.index = 0;
+        res
+    }
+
+    // 把 other 的全部元素插入到当前元素之后，位于空位置时插入到链表头部
+    pub fn splice_after(&mut self, mut other: RawLinkedList<T>) {
+        let (Some(first), Some(last)) = (other.head.take(), other.tail.take()) else {
+            return;
+        };
+        let n = std::mem::take(&mut other.len);
+        unsafe { self.link_between(self.cur, self.next_node(), first, last, n) };
+        if self.cur.is_none() {
+            self.index = self.list.len;
+        }
+    }
+
+    // 把 other 的全部元素插入到当前元素之前，位于空位置时插入到链表尾部
+    pub fn splice_before(&mut self, mut other: RawLinkedList<T>) {
+        let (Some(first), Some(last)) = (other.head.take(), other.tail.take()) else {
+            return;
+        };
+        let n = std::mem::take(&mut other.len);
+        unsafe { self.link_between(self.pre_node(), self.cur, first, last, n) };
+        self.index += n;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn to_vec(list: &RawLinkedList<i32>) -> Vec<i32> {
+        list.iter().copied().collect()
+    }
+
+    // 正反两个方向遍历都要一致
+    fn check(list: &RawLinkedList<i32>, expect: &[i32]) {
+        assert_eq!(to_vec(list), expect);
+        let mut rev = list.iter().rev().copied().collect::<Vec<i32>>();
+        rev.reverse();
+        assert_eq!(rev, expect);
+        assert_eq!(list.len(), expect.len());
+    }
+
+    #[test]
+    fn test1() {
+        let mut list = RawLinkedList::new();
+        list.push_back(2);
+        list.push_back(3);
+        list.push_front(1);
+        check(&list, &[1, 2, 3]);
+        assert_eq!(list.front(), Some(&1));
+        *list.back_mut().unwrap() = 30;
+        assert_eq!(list.pop_back(), Some(30));
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_front(), Some(2));
+        assert_eq!(list.pop_front(), None);
+        assert!(list.is_empty());
+        assert_eq!(list.back(), None);
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut list = (0..6).collect::<RawLinkedList<i32>>();
+        for x in list.iter_mut() {
+            *x *= 10;
+        }
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 6);
+        assert_eq!(iter.next(), Some(&0));
+        assert_eq!(iter.next_back(), Some(&50));
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.collect::<Vec<_>>(), [&10, &20, &30, &40]);
+
+        let mut iter = list.iter_mut();
+        let a = iter.next().unwrap();
+        let b = iter.next_back().unwrap();
+        std::mem::swap(a, b);
+        check(&list, &[50, 10, 20, 30, 40, 0]);
+        assert_eq!(
+            list.into_iter().rev().collect::<Vec<i32>>(),
+            [0, 40, 30, 20, 10, 50]
+        );
+    }
+
+    #[test]
+    fn test_append_split_reverse() {
+        let mut a = (0..3).collect::<RawLinkedList<i32>>();
+        let mut b = (3..6).collect::<RawLinkedList<i32>>();
+        a.append(&mut b);
+        assert!(b.is_empty());
+        b.append(&mut a);
+        check(&b, &[0, 1, 2, 3, 4, 5]);
+        check(&a, &[]);
+
+        let c = b.split_off(4);
+        check(&b, &[0, 1, 2, 3]);
+        check(&c, &[4, 5]);
+        let d = b.split_off(1);
+        check(&b, &[0]);
+        check(&d, &[1, 2, 3]);
+        check(&b.split_off(1), &[]);
+        check(&b.split_off(0), &[0]);
+        check(&b, &[]);
+
+        let mut e = (0..5).collect::<RawLinkedList<i32>>();
+        e.reverse();
+        check(&e, &[4, 3, 2, 1, 0]);
+        e.push_back(-1);
+        check(&e, &[4, 3, 2, 1, 0, -1]);
+    }
+
+    #[test]
+    fn test_cursor() {
+        let mut list = (0..4).collect::<RawLinkedList<i32>>();
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 1));
+        cursor.insert_before(10);
+        cursor.insert_after(11);
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.peek_prev(), Some(&mut 10));
+        assert_eq!(cursor.remove_current(), Some(1));
+        assert_eq!(cursor.current(), Some(&mut 11));
+        cursor.move_prev();
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.peek_next(), Some(&mut 0));
+        assert_eq!(cursor.remove_current(), None);
+        cursor.insert_after(-1);
+        cursor.insert_before(9);
+        check(&list, &[-1, 0, 10, 11, 2, 3, 9]);
+    }
+
+    #[test]
+    fn test_cursor_split_splice() {
+        let mut list = (0..6).collect::<RawLinkedList<i32>>();
+        let mut cursor = list.cursor_back_mut();
+        cursor.move_prev();
+        cursor.move_prev();
+        let after = cursor.split_after();
+        let before = cursor.split_before();
+        assert_eq!(cursor.index(), Some(0));
+        check(&after, &[4, 5]);
+        check(&before, &[0, 1, 2]);
+
+        cursor.splice_before(after);
+        cursor.splice_after(before);
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 3));
+        cursor.splice_after(RawLinkedList::new());
+        check(&list, &[4, 5, 3, 0, 1, 2]);
+
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_prev();
+        let all = cursor.split_before();
+        cursor.splice_before(all);
+        cursor.splice_after((6..8).collect());
+        check(&list, &[6, 7, 4, 5, 3, 0, 1, 2]);
+    }
+
+    #[test]
+    fn test_drop() {
+        use std::rc::Rc;
+        let item = Rc::new(0);
+        let mut list = (0..10).map(|_| item.clone()).collect::<RawLinkedList<_>>();
+        let tail = list.split_off(3);
+        drop(list);
+        assert_eq!(Rc::strong_count(&item), 8);
+        let mut iter = tail.into_iter();
+        iter.next();
+        drop(iter);
+        assert_eq!(Rc::strong_count(&item), 1);
+    }
+}
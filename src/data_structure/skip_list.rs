@@ -1,53 +1,74 @@
+use std::mem::MaybeUninit;
+use std::ops::{Bound, RangeBounds};
 use std::{marker::PhantomData, ptr::NonNull};
 
 use rand::{rngs::ThreadRng, thread_rng, Rng};
 
+use super::priority_queue::{Compare, MinFirst};
+
 type Node<T> = NonNull<SkipListNode<T>>;
 
 #[derive(Debug)]
 struct SkipListNode<T> {
     forwards: Vec<Option<Node<T>>>,
-    val: T,
+    // spans[i] 为 forwards[i] 跨过的元素个数，forwards[i] 为空时是到最后一个元素的距离
+    spans: Vec<usize>,
+    // 只有 head 的 val 未初始化
+    val: MaybeUninit<T>,
 }
 
 impl<T> SkipListNode<T> {
-    fn new(val: T, height: usize) -> Self {
+    fn new(val: MaybeUninit<T>, height: usize) -> Self {
         let t: Vec<Option<Node<T>>> = (0..height).map(|_| None::<Node<T>>).collect();
         SkipListNode {
             forwards: t,
+            spans: vec![0; height],
             val: val,
         }
     }
 
     fn new_ptr(val: T, height: usize) -> Node<T> {
-        let mut boxed = Box::new(Self::new(val, height));
+        let boxed = Box::new(Self::new(MaybeUninit::new(val), height));
+        NonNull::from(Box::leak(boxed))
+    }
 
-        let ptr = NonNull::new(boxed.as_mut()).expect("Create Node failed");
-        std::mem::forget(boxed);
-        return ptr;
+    fn head_ptr(height: usize) -> Node<T> {
+        let boxed = Box::new(Self::new(MaybeUninit::uninit(), height));
+        NonNull::from(Box::leak(boxed))
     }
 }
 
-pub struct SkipList<T> {
+// 元素按 cmp 升序排列，cmp.before(a, b) 表示 a 严格小于 b，需要是全序
+pub struct SkipList<T, C = MinFirst> {
     max_height: usize,
     rand: ThreadRng,
     height: usize,
     head: Node<T>,
     len: usize,
+    cmp: C,
+}
+
+impl<T: Ord> SkipList<T, MinFirst> {
+    pub fn new(max_height: Option<usize>) -> Self {
+        Self::with_compare(max_height, MinFirst)
+    }
 }
 
-impl<T> SkipList<T>
+impl<T, C> SkipList<T, C>
 where
-    T: Default,
+    C: Compare<T>,
 {
-    fn new(max_height: Option<usize>) -> Self {
-        let max_height = max_height.unwrap_or(32);
+    // 浮点数等没有实现 Ord 的类型可以传入 |a: &f64, b: &f64| a.total_cmp(b).is_lt()
+    pub fn with_compare(max_height: Option<usize>, cmp: C) -> Self {
+        // 至少保留第 0 层，否则 head 没有可以挂节点的指针
+        let max_height = max_height.unwrap_or(32).max(1);
         SkipList {
-            head: SkipListNode::new_ptr(T::default(), max_height),
+            head: SkipListNode::head_ptr(max_height),
             max_height,
             rand: thread_rng(),
             height: 0,
             len: 0,
+            cmp,
         }
     }
 
@@ -59,71 +80,227 @@ where
         return level.min(self.max_height);
     }
 
-    fn contain(&self, val: T) -> bool
-    where
-        T: PartialEq + PartialOrd,
-    {
-        if self.height == 0 {
-            return false;
+    pub fn contain(&self, val: T) -> bool {
+        self.lower_bound(&val)
+            .is_some_and(|x| !self.cmp.before(&val, x))
+    }
+
+    pub fn insert(&mut self, val: T) {
+        // 相等的元素插入到已有元素之后
+        let (mut updates, mut ranks) = self.locate(|x, _| !self.cmp.before(&val, Self::val_of(x)));
+        let mut level = self.randomLevel();
+        if level > self.height {
+            unsafe { self.head.as_mut().spans[self.height] = self.len };
+            self.height += 1;
+            level = self.height;
+            updates.push(self.head);
+            ranks.push(0);
         }
-        let mut node = unsafe { self.head.as_ref() };
-        for i in (0..self.height).rev() {
-            loop {
-                if node.forwards[i].is_none() {
-                    break;
-                }
-                let next_node = unsafe { node.forwards[i].as_ref().unwrap().as_ref() };
-                if next_node.val > val {
-                    break;
-                }
-                node = next_node;
+
+        // 新节点位于第 rank + 1 个位置
+        let rank = ranks.first().copied().unwrap_or(0);
+        let new_node = SkipListNode::new_ptr(val, level);
+        let new_node_ref = unsafe { &mut *new_node.as_ptr() };
+        for (i, prev) in updates.iter().enumerate() {
+            let p = unsafe { &mut *prev.as_ptr() };
+            if i < level {
+                new_node_ref.forwards[i] = p.forwards[i];
+                p.forwards[i] = Some(new_node);
+                new_node_ref.spans[i] = p.spans[i] - (rank - ranks[i]);
+                p.spans[i] = rank - ranks[i] + 1;
+            } else {
+                p.spans[i] += 1;
             }
         }
-        return node.val == val;
+        self.len += 1;
     }
 
-    fn insert(&mut self, val: T)
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // 从 head 开始，每层尽量向后走，返回第一个不满足 before 的节点
+    // before 在链表上必须单调：前面一段为 true，后面为 false
+    fn seek<F>(&self, before: F) -> Option<Node<T>>
     where
-        T: PartialOrd,
+        F: Fn(Node<T>) -> bool,
     {
-        let mut node = unsafe { self.head.as_mut() };
-        let mut updates: Vec<Option<Node<T>>> = vec![None; self.height + 1];
+        let prev = self.predecessors(before);
+        let node = prev.first().copied().unwrap_or(self.head);
+        Self::forward(node, 0)
+    }
+
+    // 每一层中最后一个满足 before 的节点
+    fn predecessors<F>(&self, before: F) -> Vec<Node<T>>
+    where
+        F: Fn(Node<T>) -> bool,
+    {
+        self.locate(|x, _| before(x)).0
+    }
+
+    // 同 predecessors，同时返回这些节点的位置（head 为 0，第一个元素为 1）
+    // before 的第二个参数是候选节点的位置
+    fn locate<F>(&self, before: F) -> (Vec<Node<T>>, Vec<usize>)
+    where
+        F: Fn(Node<T>, usize) -> bool,
+    {
+        let mut updates = vec![self.head; self.height];
+        let mut ranks = vec![0; self.height];
+        let mut node = self.head;
+        let mut rank = 0;
         for i in (0..self.height).rev() {
-            loop {
-                if node.forwards[i].is_none() {
-                    break;
-                }
-                let next_node = unsafe { node.forwards[i].as_mut().unwrap().as_mut() };
-                if next_node.val > val {
+            while let Some(next) = Self::forward(node, i) {
+                let next_rank = rank + unsafe { node.as_ref().spans[i] };
+                if !before(next, next_rank) {
                     break;
                 }
-                node = next_node;
+                node = next;
+                rank = next_rank;
             }
-            updates[i] = NonNull::new(node);
+            updates[i] = node;
+            ranks[i] = rank;
         }
+        (updates, ranks)
+    }
 
-        let mut level = self.randomLevel();
-        if level > self.height {
-            self.height += 1;
-            level = self.height;
-            updates[level - 1] = Some(self.head.clone());
+    fn forward(node: Node<T>, level: usize) -> Option<Node<T>> {
+        unsafe { node.as_ref().forwards[level] }
+    }
+
+    fn val_of<'a>(node: Node<T>) -> &'a T {
+        unsafe { (*node.as_ptr()).val.assume_init_ref() }
+    }
+
+    // 第一个不小于 val 的元素
+    pub fn lower_bound(&self, val: &T) -> Option<&T> {
+        self.seek(|x| self.cmp.before(Self::val_of(x), val))
+            .map(Self::val_of)
+    }
+
+    // 第一个大于 val 的元素
+    pub fn upper_bound(&self, val: &T) -> Option<&T> {
+        self.seek(|x| !self.cmp.before(val, Self::val_of(x)))
+            .map(Self::val_of)
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        Self::forward(self.head, 0).map(Self::val_of)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        let node = *self.predecessors(|_| true).first()?;
+        (node != self.head).then(|| Self::val_of(node))
+    }
+
+    // 摘下 updates[0] 之后的节点并返回它的值
+    fn unlink(&mut self, updates: &[Node<T>]) -> Option<T> {
+        let target = Self::forward(*updates.first()?, 0)?;
+        let target_box = unsafe { Box::from_raw(target.as_ptr()) };
+        for (i, prev) in updates.iter().enumerate() {
+            let prev = unsafe { &mut *prev.as_ptr() };
+            if prev.forwards[i] == Some(target) {
+                prev.forwards[i] = target_box.forwards[i];
+                prev.spans[i] += target_box.spans[i];
+            }
+            prev.spans[i] -= 1;
+        }
+        while self.height > 0 && Self::forward(self.head, self.height - 1).is_none() {
+            self.height -= 1;
         }
+        self.len -= 1;
+        Some(unsafe { target_box.val.assume_init_read() })
+    }
 
-        let mut new_node = SkipListNode::new_ptr(val, level);
-        let new_node_ref = unsafe { new_node.as_mut() };
-        for i in (0..level).rev() {
-            let p = unsafe { updates[i].unwrap().as_mut() };
-            new_node_ref.forwards[i] = p.forwards[i].clone();
-            p.forwards[i] = Some(new_node.clone());
+    // 删除一个等于 val 的元素，不存在时返回 false
+    pub fn remove(&mut self, val: &T) -> bool {
+        let updates = self.predecessors(|x| self.cmp.before(Self::val_of(x), val));
+        let found = updates
+            .first()
+            .and_then(|&x| Self::forward(x, 0))
+            .is_some_and(|x| !self.cmp.before(val, Self::val_of(x)));
+        if found {
+            self.unlink(&updates);
         }
-        self.len += 1;
+        found
     }
 
-    fn len(&self) -> usize {
-        self.len
+    // 小于 val 的元素个数，即 lower_bound 的下标
+    pub fn rank(&self, val: &T) -> usize {
+        let (_, ranks) = self.locate(|x, _| self.cmp.before(Self::val_of(x), val));
+        ranks.first().copied().unwrap_or(0)
+    }
+
+    // 第 k 小的元素，k 从 0 开始
+    pub fn select(&self, k: usize) -> Option<&T> {
+        if k >= self.len {
+            return None;
+        }
+        let (updates, _) = self.locate(|_, pos| pos <= k);
+        Self::forward(updates[0], 0).map(Self::val_of)
+    }
+
+    // 删除第 k 小的元素
+    pub fn remove_at(&mut self, k: usize) -> Option<T> {
+        if k >= self.len {
+            return None;
+        }
+        let (updates, _) = self.locate(|_, pos| pos <= k);
+        self.unlink(&updates)
+    }
+
+    pub fn pop_first(&mut self) -> Option<T> {
+        let updates = vec![self.head; self.height];
+        self.unlink(&updates)
+    }
+
+    pub fn pop_last(&mut self) -> Option<T> {
+        let last = *self.predecessors(|_| true).first()?;
+        if last == self.head {
+            return None;
+        }
+        let updates = self.predecessors(|x| x != last);
+        self.unlink(&updates)
+    }
+
+    // 按升序遍历落在 range 内的元素
+    pub fn range<R>(&self, range: R) -> Range<'_, T>
+    where
+        R: RangeBounds<T>,
+    {
+        let before = |a: &T, b: &T| self.cmp.before(a, b);
+        match (range.start_bound(), range.end_bound()) {
+            (Bound::Excluded(a), Bound::Excluded(b)) if !before(a, b) && !before(b, a) => {
+                panic!("range start and end are equal and excluded")
+            }
+            (Bound::Included(a) | Bound::Excluded(a), Bound::Included(b) | Bound::Excluded(b))
+                if before(b, a) =>
+            {
+                panic!("range start is greater than range end")
+            }
+            _ => {}
+        }
+        let cur = match range.start_bound() {
+            Bound::Included(a) => self.seek(|x| before(Self::val_of(x), a)),
+            Bound::Excluded(a) => self.seek(|x| !before(a, Self::val_of(x))),
+            Bound::Unbounded => Self::forward(self.head, 0),
+        };
+        let end = match range.end_bound() {
+            Bound::Included(b) => self.seek(|x| !before(b, Self::val_of(x))),
+            Bound::Excluded(b) => self.seek(|x| before(Self::val_of(x), b)),
+            Bound::Unbounded => None,
+        };
+        Range {
+            cur,
+            end,
+            marker: PhantomData,
+        }
     }
 
-    fn iter(&self) -> Iter<'_, T> {
+    pub fn iter(&self) -> Iter<'_, T> {
         Iter {
             cur: self.head.clone(),
             marker: PhantomData,
@@ -131,7 +308,36 @@ where
     }
 }
 
-struct Iter<'a, T: 'a> {
+impl<T, C> Drop for SkipList<T, C> {
+    fn drop(&mut self) {
+        let head = unsafe { Box::from_raw(self.head.as_ptr()) };
+        let mut node = head.forwards[0];
+        while let Some(t_node) = node {
+            let mut boxed = unsafe { Box::from_raw(t_node.as_ptr()) };
+            node = boxed.forwards[0];
+            unsafe { boxed.val.assume_init_drop() };
+        }
+    }
+}
+
+pub struct Range<'a, T: 'a> {
+    cur: Option<Node<T>>,
+    // 第一个不在范围内的节点
+    end: Option<Node<T>>,
+    marker: PhantomData<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Range<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.cur.filter(|&x| Some(x) != self.end)?;
+        let node_ref = unsafe { &*node.as_ptr() };
+        self.cur = node_ref.forwards[0];
+        Some(unsafe { node_ref.val.assume_init_ref() })
+    }
+}
+
+pub struct Iter<'a, T: 'a> {
     cur: Node<T>,
     marker: PhantomData<&'a Node<T>>,
 }
@@ -144,7 +350,7 @@ impl<'a, T> Iterator for Iter<'a, T> {
         if let Some(next_node) = node_ref.forwards[0] {
             let next_node_ref = unsafe { next_node.as_ref() };
             self.cur = next_node.clone();
-            return Some(&next_node_ref.val);
+            return Some(unsafe { next_node_ref.val.assume_init_ref() });
         }
         None
     }
@@ -152,7 +358,7 @@ impl<'a, T> Iterator for Iter<'a, T> {
 
 #[cfg(test)]
 mod test {
-    use super::SkipList;
+    use super::{Range, SkipList};
 
     #[test]
     fn test1() {
@@ -188,4 +394,218 @@ mod test {
         let mut list = SkipList::<i32>::new(Some(16));
         assert!(!list.contain(0));
     }
+
+    #[test]
+    fn test_zero_height() {
+        let list = SkipList::<i32>::new(Some(0));
+        assert!(list.first().is_none());
+        drop(list);
+
+        let mut list = SkipList::<i32>::new(Some(0));
+        for i in (0..10).rev() {
+            list.insert(i);
+        }
+        assert_eq!(list.len(), 10);
+        assert_eq!(list.height, 1);
+        assert_eq!(list.select(3), Some(&3));
+        assert_eq!(list.pop_last(), Some(9));
+        assert_eq!(list.iter().count(), 9);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut list = SkipList::<i32>::new(Some(16));
+        for i in [5, 1, 3, 3, 9, 7] {
+            list.insert(i);
+        }
+        assert!(list.remove(&3));
+        assert!(list.contain(3));
+        assert!(list.remove(&3));
+        assert!(!list.contain(3));
+        assert!(!list.remove(&3));
+        assert!(!list.remove(&100));
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.iter().copied().collect::<Vec<i32>>(), [1, 5, 7, 9]);
+
+        assert_eq!(list.pop_first(), Some(1));
+        assert_eq!(list.pop_last(), Some(9));
+        assert_eq!(list.first(), Some(&5));
+        assert_eq!(list.last(), Some(&7));
+        assert_eq!(list.pop_last(), Some(7));
+        assert_eq!(list.pop_last(), Some(5));
+        assert_eq!(list.pop_last(), None);
+        assert_eq!(list.pop_first(), None);
+        assert_eq!(list.last(), None);
+        assert!(list.is_empty());
+        assert_eq!(list.height, 0);
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut list = SkipList::<i32>::new(Some(16));
+        for i in (0..100).step_by(10) {
+            list.insert(i);
+        }
+        list.insert(50);
+        assert_eq!(list.lower_bound(&50), Some(&50));
+        assert_eq!(list.upper_bound(&50), Some(&60));
+        assert_eq!(list.lower_bound(&51), Some(&60));
+        assert_eq!(list.lower_bound(&-1), Some(&0));
+        assert_eq!(list.upper_bound(&90), None);
+
+        let collect = |r: Range<'_, i32>| r.copied().collect::<Vec<i32>>();
+        assert_eq!(collect(list.range(20..50)), [20, 30, 40]);
+        assert_eq!(collect(list.range(20..=50)), [20, 30, 40, 50, 50]);
+        assert_eq!(collect(list.range(85..)), [90]);
+        assert_eq!(collect(list.range(..15)), [0, 10]);
+        assert_eq!(collect(list.range(50..50)), []);
+        assert_eq!(collect(list.range(51..59)), []);
+        assert_eq!(list.range(..).count(), 11);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_bad_range() {
+        let list = SkipList::<i32>::new(Some(16));
+        list.range(5..3);
+    }
+
+    #[test]
+    fn test_random() {
+        use rand::{thread_rng, Rng};
+        let mut rng = thread_rng();
+        let mut list = SkipList::<i32>::new(Some(16));
+        let mut expect = vec![];
+        for _ in 0..2000 {
+            let x = rng.gen_range(0..200);
+            match rng.gen_range(0..4) {
+                0 | 1 => {
+                    list.insert(x);
+                    let pos = expect.partition_point(|&y| y <= x);
+                    expect.insert(pos, x);
+                }
+                2 => {
+                    let pos = expect.iter().position(|&y| y == x);
+                    assert_eq!(list.remove(&x), pos.is_some());
+                    pos.map(|p| expect.remove(p));
+                }
+                _ => {
+                    let (a, b) = (x.min(x / 2 + 50), x.max(x / 2 + 50));
+                    let res = list.range(a..b).copied().collect::<Vec<i32>>();
+                    let e = expect.iter().copied().filter(|y| (a..b).contains(y));
+                    assert_eq!(res, e.collect::<Vec<i32>>());
+                }
+            }
+            assert_eq!(list.len(), expect.len());
+        }
+        assert_eq!(list.iter().copied().collect::<Vec<i32>>(), expect);
+    }
+
+    #[test]
+    fn test_rank_select() {
+        let mut list = SkipList::<i32>::new(Some(16));
+        for i in [40, 10, 30, 20, 20] {
+            list.insert(i);
+        }
+        assert_eq!(list.rank(&5), 0);
+        assert_eq!(list.rank(&20), 1);
+        assert_eq!(list.rank(&25), 3);
+        assert_eq!(list.rank(&50), 5);
+        assert_eq!(list.select(0), Some(&10));
+        assert_eq!(list.select(2), Some(&20));
+        assert_eq!(list.select(4), Some(&40));
+        assert_eq!(list.select(5), None);
+
+        assert_eq!(list.remove_at(1), Some(20));
+        assert_eq!(list.remove_at(3), Some(40));
+        assert_eq!(list.remove_at(3), None);
+        assert_eq!(list.iter().copied().collect::<Vec<i32>>(), [10, 20, 30]);
+        assert_eq!(list.rank(&30), 2);
+    }
+
+    #[test]
+    fn test_random_rank() {
+        use rand::{thread_rng, Rng};
+        let mut rng = thread_rng();
+        let mut list = SkipList::<i32>::new(Some(16));
+        let mut expect = vec![];
+        for _ in 0..2000 {
+            let x = rng.gen_range(0..200);
+            match rng.gen_range(0..5) {
+                0 | 1 => {
+                    list.insert(x);
+                    let pos = expect.partition_point(|&y| y <= x);
+                    expect.insert(pos, x);
+                }
+                2 => {
+                    let k = rng.gen_range(0..expect.len() + 1);
+                    let e = (k < expect.len()).then(|| expect.remove(k));
+                    assert_eq!(list.remove_at(k), e);
+                }
+                3 => {
+                    let pos = expect.iter().position(|&y| y == x);
+                    assert_eq!(list.remove(&x), pos.is_some());
+                    pos.map(|p| expect.remove(p));
+                }
+                _ => {
+                    assert_eq!(list.pop_last(), expect.pop());
+                }
+            }
+            assert_eq!(list.rank(&x), expect.partition_point(|&y| y < x));
+            let k = rng.gen_range(0..expect.len() + 1);
+            assert_eq!(list.select(k), expect.get(k));
+        }
+        for (k, x) in expect.iter().enumerate() {
+            assert_eq!(list.select(k), Some(x));
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        use crate::data_structure::priority_queue::MaxFirst;
+        use std::num::NonZeroU32;
+
+        // 不需要 Default
+        let mut list = SkipList::new(None);
+        for i in [3, 1, 2] {
+            list.insert(NonZeroU32::new(i).unwrap());
+        }
+        assert_eq!(list.first().map(|x| x.get()), Some(1));
+
+        let mut list = SkipList::with_compare(Some(16), MaxFirst);
+        for i in [3, 1, 4, 1, 5] {
+            list.insert(i);
+        }
+        assert_eq!(list.iter().copied().collect::<Vec<i32>>(), [5, 4, 3, 1, 1]);
+        assert_eq!(list.lower_bound(&2), Some(&1));
+        assert_eq!(list.rank(&3), 2);
+        assert!(list.remove(&1));
+        assert_eq!(list.range(4..=1).copied().collect::<Vec<i32>>(), [4, 3, 1]);
+
+        let mut list = SkipList::with_compare(Some(16), |a: &f64, b: &f64| a.total_cmp(b).is_lt());
+        for x in [f64::NAN, 1.5, -0.5, f64::INFINITY] {
+            list.insert(x);
+        }
+        assert!(list.contain(f64::NAN));
+        assert_eq!(list.rank(&f64::NAN), 3);
+        assert_eq!(list.select(2), Some(&f64::INFINITY));
+        assert!(list.remove(&f64::NAN));
+        assert_eq!(list.last(), Some(&f64::INFINITY));
+    }
+
+    #[test]
+    fn test_drop() {
+        use std::rc::Rc;
+        let item = Rc::new(0);
+        #[derive(PartialEq, Eq, PartialOrd, Ord)]
+        struct Item(usize, Option<Rc<i32>>);
+        let mut list = SkipList::<Item>::new(Some(16));
+        for i in 0..100 {
+            list.insert(Item(i, Some(item.clone())));
+        }
+        list.pop_first();
+        assert_eq!(Rc::strong_count(&item), 100);
+        drop(list);
+        assert_eq!(Rc::strong_count(&item), 1);
+    }
 }
@@ -1,53 +1,82 @@
+use std::mem::MaybeUninit;
 use std::{marker::PhantomData, ptr::NonNull};
 
 use rand::{rngs::ThreadRng, thread_rng, Rng};
 
+use super::priority_queue::{Compare, MinFirst};
+
 type Node<K, V> = NonNull<SkipListNode<K, V>>;
 
 struct SkipListNode<K, V> {
-    key: K,
-    val: V,
+    // 只有 head 的 key 和 val 未初始化
+    key: MaybeUninit<K>,
+    val: MaybeUninit<V>,
     forwards: Vec<Option<Node<K, V>>>,
+    // spans[i] 为 forwards[i] 跨过的元素个数，forwards[i] 为空时是到最后一个元素的距离
+    spans: Vec<usize>,
 }
 
 impl<K, V> SkipListNode<K, V> {
-    pub fn new(key: K, val: V, height: usize) -> Self {
+    pub fn new(key: MaybeUninit<K>, val: MaybeUninit<V>, height: usize) -> Self {
         let forwards = (0..height).map(|_| None::<Node<K, V>>).collect();
         SkipListNode {
             key: key,
             val: val,
             forwards: forwards,
+            spans: vec![0; height],
         }
     }
 
     pub fn new_ptr(key: K, val: V, height: usize) -> Node<K, V> {
-        let mut boxed = Box::new(Self::new(key, val, height));
-        let ptr = NonNull::new(boxed.as_mut()).expect("failed to create node");
-        std::mem::forget(boxed);
-        return ptr;
+        let boxed = Box::new(Self::new(
+            MaybeUninit::new(key),
+            MaybeUninit::new(val),
+            height,
+        ));
+        NonNull::from(Box::leak(boxed))
+    }
+
+    pub fn head_ptr(height: usize) -> Node<K, V> {
+        let boxed = Box::new(Self::new(
+            MaybeUninit::uninit(),
+            MaybeUninit::uninit(),
+            height,
+        ));
+        NonNull::from(Box::leak(boxed))
     }
 }
 
-struct SkipListMap<K, V> {
+// 键按 cmp 升序排列，cmp.before(a, b) 表示 a 严格小于 b，需要是全序
+struct SkipListMap<K, V, C = MinFirst> {
     head: Node<K, V>,
     len: usize,
     rand: ThreadRng,
     max_height: usize,
     height: usize,
+    cmp: C,
 }
 
-impl<K, V> SkipListMap<K, V> {
-    fn new(max_height: usize) -> Self
-    where
-        K: Default,
-        V: Default,
-    {
+impl<K: Ord, V> SkipListMap<K, V, MinFirst> {
+    fn new(max_height: usize) -> Self {
+        Self::with_compare(max_height, MinFirst)
+    }
+}
+
+impl<K, V, C> SkipListMap<K, V, C>
+where
+    C: Compare<K>,
+{
+    // 浮点数等没有实现 Ord 的键可以传入 |a: &f64, b: &f64| a.total_cmp(b).is_lt()
+    fn with_compare(max_height: usize, cmp: C) -> Self {
+        // 至少保留第 0 层，否则 head 没有可以挂节点的指针
+        let max_height = max_height.max(1);
         SkipListMap {
-            head: SkipListNode::new_ptr(K::default(), V::default(), max_height),
+            head: SkipListNode::head_ptr(max_height),
             len: 0,
             rand: thread_rng(),
             max_height: max_height,
             height: 0,
+            cmp,
         }
     }
 
@@ -59,202 +88,163 @@ impl<K, V> SkipListMap<K, V> {
         return level.min(self.max_height);
     }
 
-    fn lower_bound(&self, key: &K) -> Option<Node<K, V>>
-    where
-        K: PartialOrd,
-    {
-        if self.len == 0 || self.height == 0 {
-            return None;
-        }
-        let mut node = unsafe { self.head.as_ref() };
-        let mut iter_node = &self.head;
-
-        for i in (0..self.height).rev() {
-            loop {
-                if node.forwards[i].is_none() {
-                    break;
-                }
-                let next_node_ref = unsafe { node.forwards[i].as_ref().unwrap().as_ref() };
-                if next_node_ref.key > *key {
-                    break;
-                }
-
-                iter_node = node.forwards[i].as_ref().unwrap();
-                node = next_node_ref;
-            }
-        }
-
-        if node.key < *key {
-            return node.forwards[0].clone();
-        }
-
-        return Some(iter_node.clone());
+    // 第一个键不小于 key 的节点
+    fn lower_bound(&self, key: &K) -> Option<Node<K, V>> {
+        let (updates, _) = self.locate(|x, _| self.cmp.before(Self::key_of(x), key));
+        Self::forward(updates.first().copied().unwrap_or(self.head), 0)
     }
 
-    fn upper_bound(&self, key: &K) -> Option<Node<K, V>>
-    where
-        K: PartialOrd,
-    {
-        if self.len == 0 || self.height == 0 {
-            return None;
-        }
-        let mut node = unsafe { self.head.as_ref() };
-        let mut iter_node = &self.head;
-
-        for i in (0..self.height).rev() {
-            loop {
-                if node.forwards[i].is_none() {
-                    break;
-                }
-                let next_node_ref = unsafe { node.forwards[i].as_ref().unwrap().as_ref() };
-                if next_node_ref.key > *key {
-                    break;
-                }
-                node = next_node_ref;
-                iter_node = node.forwards[i].as_ref().unwrap();
-            }
-            if node.key > *key {
-                break;
-            }
-        }
-
-        if node.key <= *key {
-            return node.forwards[0].clone();
-        }
-
-        return Some(iter_node.clone());
+    // 第一个键大于 key 的节点
+    fn upper_bound(&self, key: &K) -> Option<Node<K, V>> {
+        let (updates, _) = self.locate(|x, _| !self.cmp.before(key, Self::key_of(x)));
+        Self::forward(updates.first().copied().unwrap_or(self.head), 0)
     }
 
-    fn free_node(node: Node<K, V>) {
-        let boxed = unsafe { Box::from_raw(node.as_ptr()) };
-        std::mem::drop(boxed);
+    // 键等于 key 的节点
+    fn find(&self, key: &K) -> Option<Node<K, V>> {
+        self.lower_bound(key)
+            .filter(|&x| !self.cmp.before(key, Self::key_of(x)))
     }
 
-    fn contain(&self, key: &K) -> bool
-    where
-        K: PartialOrd,
-    {
-        let it = self.lower_bound(&key);
-        return it.is_some_and(|x| {
-            let t_node = unsafe { x.as_ref() };
-            return t_node.key == *key && !std::ptr::eq(t_node, unsafe { self.head.as_ref() });
-        });
+    fn contain(&self, key: &K) -> bool {
+        self.find(key).is_some()
     }
 
-    fn get_key(&self, key: &K) -> Option<&V>
-    where
-        K: PartialOrd,
-    {
-        let it = self.lower_bound(key);
+    fn get_key(&self, key: &K) -> Option<&V> {
+        let node = self.find(key)?;
+        Some(unsafe { (*node.as_ptr()).val.assume_init_ref() })
+    }
 
-        return it.map_or(None, |x| {
-            let t_node = unsafe { x.as_ref() };
-            if t_node.key != *key || std::ptr::eq(t_node, unsafe { self.head.as_ref() }) {
-                return None;
-            }
+    fn forward(node: Node<K, V>, level: usize) -> Option<Node<K, V>> {
+        unsafe { node.as_ref().forwards[level] }
+    }
 
-            return Some(&t_node.val);
-        });
+    fn key_of<'a>(node: Node<K, V>) -> &'a K {
+        unsafe { (*node.as_ptr()).key.assume_init_ref() }
     }
 
-    fn insert(&mut self, key: K, val: V)
+    // 每一层中最后一个满足 before 的节点及其位置（head 为 0，第一个元素为 1）
+    // before 的第二个参数是候选节点的位置
+    fn locate<F>(&self, before: F) -> (Vec<Node<K, V>>, Vec<usize>)
     where
-        K: PartialOrd,
+        F: Fn(Node<K, V>, usize) -> bool,
     {
-        let mut node = unsafe { self.head.as_mut() };
-        let mut updates: Vec<Option<Node<K, V>>> = vec![None; self.height + 1];
-
+        let mut updates = vec![self.head; self.height];
+        let mut ranks = vec![0; self.height];
+        let mut node = self.head;
+        let mut rank = 0;
         for i in (0..self.height).rev() {
-            loop {
-                if node.forwards[i].is_none() {
-                    break;
-                }
-                let next_node_ref = unsafe { node.forwards[i].as_mut().unwrap().as_mut() };
-                if next_node_ref.key > key {
+            while let Some(next) = Self::forward(node, i) {
+                let next_rank = rank + unsafe { node.as_ref().spans[i] };
+                if !before(next, next_rank) {
                     break;
                 }
-                node = next_node_ref;
+                node = next;
+                rank = next_rank;
             }
-            updates[i] = NonNull::new(node);
+            updates[i] = node;
+            ranks[i] = rank;
         }
+        (updates, ranks)
+    }
 
-        if node.key == key && unsafe { !std::ptr::eq(node, self.head.as_ref()) } {
+    fn insert(&mut self, key: K, val: V) {
+        let (mut updates, mut ranks) = self.locate(|x, _| self.cmp.before(Self::key_of(x), &key));
+        let next = updates.first().and_then(|&x| Self::forward(x, 0));
+        if let Some(mut next) = next.filter(|&x| !self.cmp.before(&key, Self::key_of(x))) {
             // update node
-            node.val = val;
+            unsafe { *next.as_mut().val.assume_init_mut() = val };
             return;
         }
 
         let mut level = self.random_level();
 
         if level > self.height {
+            unsafe { self.head.as_mut().spans[self.height] = self.len };
             self.height += 1;
             level = self.height;
-            updates[level - 1] = Some(self.head.clone());
+            updates.push(self.head);
+            ranks.push(0);
         }
 
+        // 新节点位于第 rank + 1 个位置
+        let rank = ranks.first().copied().unwrap_or(0);
         let mut new_node = SkipListNode::new_ptr(key, val, level);
         let new_node_ref = unsafe { new_node.as_mut() };
 
-        for i in (0..level).rev() {
-            let t_node = unsafe { updates[i].unwrap().as_mut() };
-            new_node_ref.forwards[i] = t_node.forwards[i].clone();
-            t_node.forwards[i] = Some(new_node.clone());
+        for (i, prev) in updates.iter().enumerate() {
+            let t_node = unsafe { &mut *prev.as_ptr() };
+            if i < level {
+                new_node_ref.forwards[i] = t_node.forwards[i];
+                t_node.forwards[i] = Some(new_node);
+                new_node_ref.spans[i] = t_node.spans[i] - (rank - ranks[i]);
+                t_node.spans[i] = rank - ranks[i] + 1;
+            } else {
+                t_node.spans[i] += 1;
+            }
         }
 
         self.len += 1;
     }
 
-    fn erase(&mut self, key: &K)
-    where
-        K: PartialOrd,
-    {
-        let mut node = unsafe { self.head.as_ref() };
-        let mut node_iter = &self.head;
-        let mut updates: Vec<Option<Node<K, V>>> = vec![None; self.height];
-
-        let mut target_ptr: Option<Node<K, V>> = None;
-        for i in (0..self.height).rev() {
-            loop {
-                if node.forwards[i].is_none() {
-                    break;
-                }
-                let next_node_ref = unsafe { node.forwards[i].as_ref().unwrap().as_ref() };
-                if next_node_ref.key >= *key {
-                    break;
-                }
-                node_iter = node.forwards[i].as_ref().unwrap();
-                node = next_node_ref;
+    // 摘下 updates[0] 之后的节点并返回它的键值
+    fn unlink(&mut self, updates: &[Node<K, V>]) -> Option<(K, V)> {
+        let target = Self::forward(*updates.first()?, 0)?;
+        let target_box = unsafe { Box::from_raw(target.as_ptr()) };
+        for (i, prev) in updates.iter().enumerate() {
+            let prev = unsafe { &mut *prev.as_ptr() };
+            if prev.forwards[i] == Some(target) {
+                prev.forwards[i] = target_box.forwards[i];
+                prev.spans[i] += target_box.spans[i];
             }
-
-            node.forwards[i].as_ref().and_then(|x| {
-                let next_node_ref = unsafe { x.as_ref() };
-                if next_node_ref.key == *key {
-                    updates[i] = Some(node_iter.clone());
-                    if target_ptr.is_none() {
-                        target_ptr = Some(x.clone());
-                    }
-                }
-                return Some(true);
-            });
+            prev.spans[i] -= 1;
+        }
+        while self.height > 0 && Self::forward(self.head, self.height - 1).is_none() {
+            self.height -= 1;
+        }
+        self.len -= 1;
+        unsafe {
+            Some((
+                target_box.key.assume_init_read(),
+                target_box.val.assume_init_read(),
+            ))
         }
+    }
 
-        if target_ptr.is_none() {
-            return;
+    fn erase(&mut self, key: &K) {
+        let (updates, _) = self.locate(|x, _| self.cmp.before(Self::key_of(x), key));
+        let found = updates
+            .first()
+            .and_then(|&x| Self::forward(x, 0))
+            .is_some_and(|x| !self.cmp.before(key, Self::key_of(x)));
+        if found {
+            self.unlink(&updates);
         }
+    }
 
-        updates
-            .iter_mut()
-            .enumerate()
-            .filter(|(_, x)| x.is_some())
-            .for_each(|(lev, node)| {
-                let node_ref = unsafe { node.unwrap().as_mut() };
-                let next_node = node_ref.forwards[lev].as_mut();
-                let next_node_ref = unsafe { next_node.unwrap().as_mut() };
-                node_ref.forwards[lev] = next_node_ref.forwards[lev].clone();
-            });
+    // 小于 key 的键的个数
+    fn rank(&self, key: &K) -> usize {
+        let (_, ranks) = self.locate(|x, _| self.cmp.before(Self::key_of(x), key));
+        ranks.first().copied().unwrap_or(0)
+    }
 
-        Self::free_node(target_ptr.unwrap());
-        self.len -= 1;
+    // 第 k 小的键值对，k 从 0 开始
+    fn select(&self, k: usize) -> Option<(&K, &V)> {
+        if k >= self.len {
+            return None;
+        }
+        let (updates, _) = self.locate(|_, pos| pos <= k);
+        let node = unsafe { &*Self::forward(updates[0], 0)?.as_ptr() };
+        unsafe { Some((node.key.assume_init_ref(), node.val.assume_init_ref())) }
+    }
+
+    // 删除第 k 小的键值对
+    fn remove_at(&mut self, k: usize) -> Option<(K, V)> {
+        if k >= self.len {
+            return None;
+        }
+        let (updates, _) = self.locate(|_, pos| pos <= k);
+        self.unlink(&updates)
     }
 
     fn iter_key(&self) -> IterKey<'_, K, V> {
@@ -274,14 +264,18 @@ impl<K, V> SkipListMap<K, V> {
     }
 }
 
-impl<K, V> Drop for SkipListMap<K, V> {
+impl<K, V, C> Drop for SkipListMap<K, V, C> {
     fn drop(&mut self) {
-        let mut node = Some(self.head.clone());
+        let head = unsafe { Box::from_raw(self.head.as_ptr()) };
+        let mut node = head.forwards[0];
 
         while let Some(t_node) = node {
-            let node_ref = unsafe { t_node.as_ref() };
-            node = node_ref.forwards[0].clone();
-            Self::free_node(t_node);
+            let mut boxed = unsafe { Box::from_raw(t_node.as_ptr()) };
+            node = boxed.forwards[0];
+            unsafe {
+                boxed.key.assume_init_drop();
+                boxed.val.assume_init_drop();
+            }
         }
     }
 }
@@ -299,7 +293,12 @@ impl<'a, K, V> Iterator for IterKeyVal<'a, K, V> {
         if let Some(next) = cur_ref.forwards[0] {
             let next_ref = unsafe { next.as_ref() };
             self.cur = next.clone();
-            return Some((&next_ref.key, &next_ref.val));
+            return unsafe {
+                Some((
+                    next_ref.key.assume_init_ref(),
+                    next_ref.val.assume_init_ref(),
+                ))
+            };
         }
         None
     }
@@ -318,7 +317,7 @@ impl<'a, K, V> Iterator for IterKey<'a, K, V> {
         if let Some(next) = cur_ref.forwards[0] {
             let next_ref = unsafe { next.as_ref() };
             self.cur = next.clone();
-            return Some(&next_ref.key);
+            return Some(unsafe { next_ref.key.assume_init_ref() });
         }
         None
     }
@@ -347,7 +346,7 @@ mod test {
 
     #[test]
     fn test_float_keys() {
-        let mut map: SkipListMap<f32, i32> = SkipListMap::new(16);
+        let mut map = SkipListMap::with_compare(16, |a: &f32, b: &f32| a.total_cmp(b).is_lt());
 
         // 插入浮点数键值对
         map.insert(5.5, 5);
@@ -434,6 +433,110 @@ mod test {
         assert_eq!(keys_val, except);
     }
 
+    #[test]
+    fn test_rank_select() {
+        let mut map: SkipListMap<i32, char> = SkipListMap::new(16);
+        for (k, v) in [(30, 'c'), (10, 'a'), (20, 'b'), (40, 'd')] {
+            map.insert(k, v);
+        }
+        map.insert(20, 'B');
+        assert_eq!(map.len, 4);
+        assert_eq!(map.rank(&20), 1);
+        assert_eq!(map.rank(&25), 2);
+        assert_eq!(map.rank(&99), 4);
+        assert_eq!(map.select(1), Some((&20, &'B')));
+        assert_eq!(map.select(4), None);
+
+        assert_eq!(map.remove_at(0), Some((10, 'a')));
+        assert_eq!(map.remove_at(3), None);
+        assert_eq!(map.select(0), Some((&20, &'B')));
+        map.erase(&30);
+        assert_eq!(map.rank(&40), 1);
+        assert_eq!(map.iter_key().copied().collect::<Vec<i32>>(), [20, 40]);
+    }
+
+    #[test]
+    fn test_random_rank() {
+        use rand::{thread_rng, Rng};
+        let mut rng = thread_rng();
+        let mut map: SkipListMap<i32, i32> = SkipListMap::new(16);
+        let mut expect: Vec<i32> = vec![];
+        for _ in 0..2000 {
+            let x = rng.gen_range(0..300);
+            match rng.gen_range(0..3) {
+                0 => {
+                    map.insert(x, -x);
+                    if let Err(pos) = expect.binary_search(&x) {
+                        expect.insert(pos, x);
+                    }
+                }
+                1 => {
+                    let k = rng.gen_range(0..expect.len() + 1);
+                    let e = (k < expect.len()).then(|| expect.remove(k));
+                    assert_eq!(map.remove_at(k), e.map(|x| (x, -x)));
+                }
+                _ => {
+                    map.erase(&x);
+                    if let Ok(pos) = expect.binary_search(&x) {
+                        expect.remove(pos);
+                    }
+                }
+            }
+            assert_eq!(map.len, expect.len());
+            assert_eq!(map.rank(&x), expect.partition_point(|&y| y < x));
+        }
+        for (k, x) in expect.iter().enumerate() {
+            assert_eq!(map.select(k), Some((x, &-x)));
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        use std::rc::Rc;
+
+        // 键和值都不需要 Default
+        let item = Rc::new(0);
+        let mut map = SkipListMap::new(16);
+        for i in ["b", "a", "c"] {
+            map.insert(
+                std::num::NonZeroU8::new(i.as_bytes()[0]).unwrap(),
+                item.clone(),
+            );
+        }
+        let keys = map.iter_key().map(|x| x.get()).collect::<Vec<u8>>();
+        assert_eq!(keys, b"abc");
+        assert_eq!(Rc::strong_count(&item), 4);
+        drop(map);
+        assert_eq!(Rc::strong_count(&item), 1);
+
+        let mut map = SkipListMap::with_compare(16, |a: &f64, b: &f64| a.total_cmp(b).is_lt());
+        map.insert(f64::NAN, 1);
+        map.insert(0.0, 2);
+        map.insert(f64::NAN, 3);
+        assert_eq!(map.len, 2);
+        assert_eq!(map.get_key(&f64::NAN), Some(&3));
+        assert_eq!(map.get_key(&-0.0), None);
+        map.erase(&f64::NAN);
+        assert!(!map.contain(&f64::NAN));
+        assert!(map.upper_bound(&-1.0).is_some());
+    }
+
+    #[test]
+    fn test_zero_height() {
+        let map: SkipListMap<i32, i32> = SkipListMap::new(0);
+        assert!(!map.contain(&1));
+        drop(map);
+
+        let mut map: SkipListMap<i32, i32> = SkipListMap::new(0);
+        for i in 0..10 {
+            map.insert(i, i * i);
+        }
+        assert_eq!(map.len, 10);
+        assert_eq!(map.get_key(&3), Some(&9));
+        assert_eq!(map.remove_at(0), Some((0, 0)));
+        assert_eq!(map.iter_key().count(), 9);
+    }
+
     #[test]
     fn test_drop() {
         let mut map: SkipListMap<i32, i32> = SkipListMap::new(16);
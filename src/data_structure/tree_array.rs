@@ -1,6 +1,6 @@
 use std::ops::{Add, AddAssign};
 
-struct TreeArray<T> {
+pub struct TreeArray<T> {
     tree: Vec<T>,
 }
 
@@ -12,7 +12,7 @@ where
         x & -x
     }
 
-    pub fn query(&mut self, r: usize) -> Option<T> {
+    pub fn query(&self, r: usize) -> Option<T> {
         let mut i = (r + 1) as i32;
         let mut res = T::default();
         while i > 0 {
@@ -23,8 +23,7 @@ where
     }
 
     pub fn add(&mut self, idx: usize, val: T) {
-        let idx = (idx + 1) as i32;
-        let mut i = 1i32;
+        let mut i = (idx + 1) as i32;
         while i < self.tree.len() as i32 {
             self.tree[i as usize] += val;
             i += Self::lowbit(i);
@@ -53,8 +52,18 @@ mod test {
     #[test]
     fn test1() {
         let nums = vec![1, 2, 3, 4, 5];
-        let mut arr_tree = TreeArray::new(&nums);
+        let arr_tree = TreeArray::new(&nums);
         assert_eq!(arr_tree.query(0), Some(1));
         assert_eq!(arr_tree.query(4), Some(15));
     }
+
+    #[test]
+    fn test_add() {
+        let nums = vec![1, 2, 3, 4, 5];
+        let mut arr_tree = TreeArray::new(&nums);
+        arr_tree.add(2, 10);
+        assert_eq!(arr_tree.query(1), Some(3));
+        assert_eq!(arr_tree.query(2), Some(16));
+        assert_eq!(arr_tree.query(4), Some(25));
+    }
 }
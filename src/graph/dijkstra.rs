@@ -1,5 +1,9 @@
 use std::collections::BinaryHeap;
 
+use crate::data_structure::fibonacci_heap::{FibonacciHeap, Handle};
+use crate::data_structure::indexed_priority_queue::IndexedPriorityQueue;
+use crate::data_structure::priority_queue::MinFirst;
+
 #[derive(PartialEq, Clone, Copy, Eq, Ord, Debug)]
 struct Node {
     idx: usize,
@@ -38,6 +42,91 @@ pub fn dijkstra(map: &Vec<Vec<(usize, i32)>>, start: usize, n: usize) -> Vec<i32
     dis
 }
 
+// 以顶点编号为句柄、支持减小距离的最小优先队列
+pub trait DistanceQueue {
+    fn with_vertices(n: usize) -> Self;
+
+    // 顶点不在队列中时插入，否则把距离改为 dis（dis 不大于原距离）
+    fn push_or_decrease(&mut self, v: usize, dis: i32);
+
+    fn pop_min(&mut self) -> Option<(usize, i32)>;
+}
+
+impl DistanceQueue for IndexedPriorityQueue<i32> {
+    fn with_vertices(n: usize) -> Self {
+        IndexedPriorityQueue::new(n)
+    }
+
+    fn push_or_decrease(&mut self, v: usize, dis: i32) {
+        if !self.decrease_key(v, dis) {
+            self.push(v, dis);
+        }
+    }
+
+    fn pop_min(&mut self) -> Option<(usize, i32)> {
+        self.pop()
+    }
+}
+
+// 斐波那契堆按 (距离, 顶点) 排序，另外记录每个顶点对应的句柄
+pub struct FibonacciQueue {
+    heap: FibonacciHeap<(i32, usize), MinFirst>,
+    handles: Vec<Option<Handle<(i32, usize)>>>,
+}
+
+impl DistanceQueue for FibonacciQueue {
+    fn with_vertices(n: usize) -> Self {
+        FibonacciQueue {
+            heap: FibonacciHeap::new_min(None),
+            handles: vec![None; n],
+        }
+    }
+
+    fn push_or_decrease(&mut self, v: usize, dis: i32) {
+        match &self.handles[v] {
+            Some(h) if h.is_valid() => {
+                self.heap.decrease_key(h, (dis, v));
+            }
+            _ => self.handles[v] = Some(self.heap.push((dis, v))),
+        }
+    }
+
+    fn pop_min(&mut self) -> Option<(usize, i32)> {
+        self.heap.pop().map(|(dis, v)| (v, dis))
+    }
+}
+
+// 每个顶点在队列中至多出现一次，不需要懒删除
+pub fn dijkstra_with<Q: DistanceQueue>(
+    map: &[Vec<(usize, i32)>],
+    start: usize,
+    n: usize,
+) -> Vec<i32> {
+    let mut que = Q::with_vertices(n);
+    let mut dis = vec![i32::MAX / 2; n];
+
+    dis[start] = 0;
+    que.push_or_decrease(start, 0);
+
+    while let Some((u, d)) = que.pop_min() {
+        for &(v, w) in &map[u] {
+            if dis[v] > d + w {
+                dis[v] = d + w;
+                que.push_or_decrease(v, dis[v]);
+            }
+        }
+    }
+    dis
+}
+
+pub fn dijkstra_indexed(map: &[Vec<(usize, i32)>], start: usize, n: usize) -> Vec<i32> {
+    dijkstra_with::<IndexedPriorityQueue<i32>>(map, start, n)
+}
+
+pub fn dijkstra_fibonacci(map: &[Vec<(usize, i32)>], start: usize, n: usize) -> Vec<i32> {
+    dijkstra_with::<FibonacciQueue>(map, start, n)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -60,4 +149,30 @@ mod test {
         let res = dijkstra(&map, 0, 4);
         assert_eq!(res, [0, 10, 3, 11]);
     }
+
+    #[test]
+    fn test_indexed() {
+        let mut map: Vec<Vec<(usize, i32)>> = vec![vec![]; 4];
+        map[0].extend_from_slice(&[(1, 10), (2, 3)]);
+        map[1].extend_from_slice(&[(3, 1)]);
+        map[2].extend_from_slice(&[(3, 30), (1, 2)]);
+        assert_eq!(dijkstra_indexed(&map, 0, 4), [0, 5, 3, 6]);
+        assert_eq!(dijkstra_indexed(&map, 0, 4), dijkstra(&map, 0, 4));
+        assert_eq!(dijkstra_fibonacci(&map, 0, 4), [0, 5, 3, 6]);
+    }
+
+    #[test]
+    fn test_random() {
+        use rand::{thread_rng, Rng};
+        let mut rng = thread_rng();
+        let n = 200;
+        let mut map: Vec<Vec<(usize, i32)>> = vec![vec![]; n];
+        for _ in 0..2000 {
+            let u = rng.gen_range(0..n);
+            let v = rng.gen_range(0..n);
+            map[u].push((v, rng.gen_range(0..100)));
+        }
+        assert_eq!(dijkstra_indexed(&map, 0, n), dijkstra(&map, 0, n));
+        assert_eq!(dijkstra_fibonacci(&map, 0, n), dijkstra(&map, 0, n));
+    }
 }
@@ -1,2 +1,2 @@
-mod Dijkstra;
-mod spfa;
+pub mod dijkstra;
+pub mod spfa;
@@ -1,6 +1,22 @@
+mod bench;
 mod data_structure;
 mod graph;
 mod sort;
 mod tree;
 
-fn main() {}
+fn usage() {
+    eprintln!("usage: cargo run --release -- <sort|heap> [n]");
+}
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    let n = args
+        .get(1)
+        .and_then(|x| x.parse().ok())
+        .unwrap_or(1_000_000);
+    match args.first().map(|x| x.as_str()) {
+        Some("sort") => bench::sort::run(n),
+        Some("heap") => bench::heap::run(n),
+        _ => usage(),
+    }
+}
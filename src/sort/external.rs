@@ -0,0 +1,461 @@
+use std::cmp::Ordering;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+use super::mergesort::MergeSorter;
+use crate::data_structure::priority_queue::{Compare, PriorityQueue};
+
+// 记录的序列化方式
+pub trait RecordCodec<T> {
+    fn encode<W: Write>(&self, item: &T, w: &mut W) -> io::Result<()>;
+
+    // 读到文件末尾时返回 Ok(None)
+    fn decode<R: BufRead>(&self, r: &mut R) -> io::Result<Option<T>>;
+
+    // 记录在内存中占用的字节数，用于控制内存预算
+    fn size_hint(&self, _item: &T) -> usize {
+        std::mem::size_of::<T>()
+    }
+}
+
+// 每行一条记录，记录本身不能包含换行符
+#[derive(Clone, Copy, Default)]
+pub struct LineCodec;
+
+impl RecordCodec<String> for LineCodec {
+    fn encode<W: Write>(&self, item: &String, w: &mut W) -> io::Result<()> {
+        w.write_all(item.as_bytes())?;
+        w.write_all(b"\n")
+    }
+
+    fn decode<R: BufRead>(&self, r: &mut R) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        if r.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        if line.ends_with('\n') {
+            line.pop();
+        }
+        Ok(Some(line))
+    }
+
+    fn size_hint(&self, item: &String) -> usize {
+        std::mem::size_of::<String>() + item.capacity()
+    }
+}
+
+// 数值按小端定长字节存储
+#[derive(Clone, Copy, Default)]
+pub struct LeBytesCodec;
+
+macro_rules! impl_le_bytes_codec {
+    ($($t:ty),*) => {
+        $(impl RecordCodec<$t> for LeBytesCodec {
+            fn encode<W: Write>(&self, item: &$t, w: &mut W) -> io::Result<()> {
+                w.write_all(&item.to_le_bytes())
+            }
+
+            fn decode<R: BufRead>(&self, r: &mut R) -> io::Result<Option<$t>> {
+                if r.fill_buf()?.is_empty() {
+                    return Ok(None);
+                }
+                let mut buf = [0u8; std::mem::size_of::<$t>()];
+                r.read_exact(&mut buf)?;
+                Ok(Some(<$t>::from_le_bytes(buf)))
+            }
+        })*
+    };
+}
+
+impl_le_bytes_codec!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+static RUN_ID: AtomicUsize = AtomicUsize::new(0);
+
+// 默认一次最多归并的有序段数，同时也是归并时最多打开的文件数
+const DEFAULT_MAX_FAN_IN: usize = 64;
+
+// 临时文件中的一个有序段，析构时删除文件
+struct Run {
+    path: PathBuf,
+}
+
+impl Drop for Run {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+// 多路归并堆的比较器，键相同时所属有序段靠前的记录优先
+pub struct RunCompare<F> {
+    cmp: Rc<F>,
+}
+
+impl<T, F> Compare<(T, usize)> for RunCompare<F>
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    fn before(&self, a: &(T, usize), b: &(T, usize)) -> bool {
+        match (self.cmp)(&a.0, &b.0) {
+            Ordering::Less => true,
+            Ordering::Greater => false,
+            Ordering::Equal => a.1 < b.1,
+        }
+    }
+}
+
+pub struct ExternalSorter<C> {
+    codec: C,
+    memory_budget: usize,
+    max_fan_in: usize,
+    tmp_dir: PathBuf,
+}
+
+impl<C> ExternalSorter<C> {
+    // memory_budget 为每个有序段在内存中允许占用的字节数，tmp_dir 默认为系统临时目录
+    pub fn new(codec: C, memory_budget: usize, tmp_dir: Option<PathBuf>) -> Self {
+        ExternalSorter {
+            codec,
+            memory_budget,
+            max_fan_in: DEFAULT_MAX_FAN_IN,
+            tmp_dir: tmp_dir.unwrap_or_else(std::env::temp_dir),
+        }
+    }
+
+    // 有序段多于 max_fan_in 时先分组归并成更长的有序段，直到能一次归并完
+    pub fn set_max_fan_in(&mut self, max_fan_in: usize) {
+        self.max_fan_in = max_fan_in.max(2);
+    }
+
+    fn write_run<G>(&self, fill: G) -> io::Result<Run>
+    where
+        G: FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+    {
+        let run = Run {
+            path: self.tmp_dir.join(format!(
+                "external-sort-{}-{}.run",
+                std::process::id(),
+                RUN_ID.fetch_add(1, AtomicOrdering::Relaxed)
+            )),
+        };
+        let mut writer = BufWriter::new(File::create(&run.path)?);
+        fill(&mut writer)?;
+        writer.flush()?;
+        Ok(run)
+    }
+
+    fn write_chunk<T>(&self, chunk: &[T]) -> io::Result<Run>
+    where
+        C: RecordCodec<T>,
+    {
+        self.write_run(|w| chunk.iter().try_for_each(|item| self.codec.encode(item, w)))
+    }
+
+    // 多路归并 runs，runs 中靠前的有序段在键相同时优先
+    fn merge_runs<T, F>(
+        &self,
+        runs: Vec<Run>,
+        cmp: Rc<F>,
+    ) -> io::Result<ExternalSortIter<'_, T, C, F>>
+    where
+        F: Fn(&T, &T) -> Ordering,
+        C: RecordCodec<T>,
+    {
+        let mut iter = ExternalSortIter {
+            codec: &self.codec,
+            memory: vec![].into_iter(),
+            runs: Vec::with_capacity(runs.len()),
+            heap: PriorityQueue::with_compare(None, RunCompare { cmp }),
+            error: None,
+        };
+        for (idx, run) in runs.into_iter().enumerate() {
+            let mut reader = BufReader::new(File::open(&run.path)?);
+            if let Some(item) = self.codec.decode(&mut reader)? {
+                iter.heap.push((item, idx));
+            }
+            iter.runs.push((reader, run));
+        }
+        Ok(iter)
+    }
+
+    // 稳定排序，返回按 cmp 升序产生记录的迭代器
+    pub fn sort_by<T, I, F>(&self, input: I, cmp: F) -> io::Result<ExternalSortIter<'_, T, C, F>>
+    where
+        T: Clone,
+        I: IntoIterator<Item = T>,
+        F: Fn(&T, &T) -> Ordering,
+        C: RecordCodec<T>,
+    {
+        let cmp = Rc::new(cmp);
+        let mut sorter = MergeSorter::new();
+        let mut runs = vec![];
+        let mut chunk = vec![];
+        let mut chunk_size = 0;
+
+        for item in input {
+            chunk_size += self.codec.size_hint(&item);
+            chunk.push(item);
+            if chunk_size >= self.memory_budget {
+                sorter.sort_by(&mut chunk, |a, b| cmp(a, b));
+                runs.push(self.write_chunk(&chunk)?);
+                chunk.clear();
+                chunk_size = 0;
+            }
+        }
+        sorter.sort_by(&mut chunk, |a, b| cmp(a, b));
+
+        // 数据能一次放入内存时不需要写临时文件
+        if runs.is_empty() {
+            let mut iter = self.merge_runs(vec![], cmp)?;
+            iter.memory = chunk.into_iter();
+            return Ok(iter);
+        }
+        if !chunk.is_empty() {
+            runs.push(self.write_chunk(&chunk)?);
+        }
+        drop(chunk);
+
+        // 相邻的有序段按顺序分组归并，保持排序稳定
+        while runs.len() > self.max_fan_in {
+            let mut merged = Vec::with_capacity(runs.len().div_ceil(self.max_fan_in));
+            let mut rest = runs.into_iter();
+            loop {
+                let group = rest.by_ref().take(self.max_fan_in).collect::<Vec<Run>>();
+                if group.is_empty() {
+                    break;
+                }
+                let iter = self.merge_runs(group, cmp.clone())?;
+                merged.push(self.write_run(|w| {
+                    for item in iter {
+                        self.codec.encode(&item?, w)?;
+                    }
+                    Ok(())
+                })?);
+            }
+            runs = merged;
+        }
+        self.merge_runs(runs, cmp)
+    }
+
+    pub fn sort<T, I>(&self, input: I) -> io::Result<ExternalSortIter<'_, T, C, OrdCompare<T>>>
+    where
+        T: Ord + Clone,
+        I: IntoIterator<Item = T>,
+        C: RecordCodec<T>,
+    {
+        self.sort_by(input, T::cmp as OrdCompare<T>)
+    }
+
+    // 从 input 文件读取记录，排序后写入 output 文件
+    pub fn sort_file_by<T, F>(&self, input: &Path, output: &Path, cmp: F) -> io::Result<()>
+    where
+        T: Clone,
+        F: Fn(&T, &T) -> Ordering,
+        C: RecordCodec<T>,
+    {
+        let mut reader = BufReader::new(File::open(input)?);
+        let mut read_error = None;
+        let source = std::iter::from_fn(|| match self.codec.decode(&mut reader) {
+            Ok(x) => x,
+            Err(e) => {
+                read_error = Some(e);
+                None
+            }
+        });
+        let sorted = self.sort_by(source, cmp);
+        if let Some(e) = read_error {
+            return Err(e);
+        }
+        let mut writer = BufWriter::new(File::create(output)?);
+        for item in sorted? {
+            self.codec.encode(&item?, &mut writer)?;
+        }
+        writer.flush()
+    }
+
+    pub fn sort_file<T>(&self, input: &Path, output: &Path) -> io::Result<()>
+    where
+        T: Ord + Clone,
+        C: RecordCodec<T>,
+    {
+        self.sort_file_by(input, output, T::cmp)
+    }
+}
+
+pub type OrdCompare<T> = fn(&T, &T) -> Ordering;
+
+pub struct ExternalSortIter<'a, T, C, F> {
+    codec: &'a C,
+    memory: std::vec::IntoIter<T>,
+    // 每个有序段的读取端，读取端先于文件被析构
+    runs: Vec<(BufReader<File>, Run)>,
+    // (当前记录, 所属有序段)，多路归并用的堆
+    heap: PriorityQueue<(T, usize), RunCompare<F>>,
+    error: Option<io::Error>,
+}
+
+impl<T, C, F> Iterator for ExternalSortIter<'_, T, C, F>
+where
+    C: RecordCodec<T>,
+    F: Fn(&T, &T) -> Ordering,
+{
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.error.take() {
+            self.runs.clear();
+            self.heap.clear();
+            return Some(Err(e));
+        }
+        if let Some(item) = self.memory.next() {
+            return Some(Ok(item));
+        }
+        let (item, idx) = self.heap.pop()?;
+        match self.codec.decode(&mut self.runs[idx].0) {
+            Ok(Some(next)) => self.heap.push((next, idx)),
+            Ok(None) => {}
+            Err(e) => self.error = Some(e),
+        }
+        Some(Ok(item))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{thread_rng, Rng};
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[derive(Clone, Copy)]
+    struct PairCodec;
+
+    impl RecordCodec<(u32, u32)> for PairCodec {
+        fn encode<W: Write>(&self, item: &(u32, u32), w: &mut W) -> io::Result<()> {
+            LeBytesCodec.encode(&item.0, w)?;
+            LeBytesCodec.encode(&item.1, w)
+        }
+
+        fn decode<R: BufRead>(&self, r: &mut R) -> io::Result<Option<(u32, u32)>> {
+            let a = LeBytesCodec.decode(r)?;
+            let b = LeBytesCodec.decode(r)?;
+            Ok(a.zip(b))
+        }
+    }
+
+    #[test]
+    fn test_in_memory() {
+        let sorter = ExternalSorter::new(LeBytesCodec, 1 << 20, None);
+        let res = sorter
+            .sort(vec![5i64, -1, 3, 2])
+            .unwrap()
+            .collect::<io::Result<Vec<i64>>>()
+            .unwrap();
+        assert_eq!(res, [-1, 2, 3, 5]);
+
+        let res = sorter.sort(Vec::<i64>::new()).unwrap().count();
+        assert_eq!(res, 0);
+    }
+
+    #[test]
+    fn test_many_runs() {
+        let dir = test_dir("external-sort-many-runs");
+        let mut rng = thread_rng();
+        let nums = (0..20000).map(|_| rng.gen::<i32>()).collect::<Vec<i32>>();
+        let mut expect = nums.clone();
+        expect.sort();
+
+        // 每个有序段约 1000 条记录
+        let sorter = ExternalSorter::new(LeBytesCodec, 4000, Some(dir.clone()));
+        let iter = sorter.sort(nums).unwrap();
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 20);
+        let res = iter.collect::<io::Result<Vec<i32>>>().unwrap();
+        assert_eq!(res, expect);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_multi_pass() {
+        let dir = test_dir("external-sort-multi-pass");
+        let mut rng = thread_rng();
+        let records = (0..20000)
+            .map(|i| (rng.gen_range(0..100), i))
+            .collect::<Vec<(u32, u32)>>();
+        let mut expect = records.clone();
+        expect.sort_by_key(|x| x.0);
+
+        // 约 250 个有序段，每次最多归并 4 个
+        let mut sorter = ExternalSorter::new(PairCodec, 640, Some(dir.clone()));
+        sorter.set_max_fan_in(4);
+        let iter = sorter
+            .sort_by(records, |a: &(u32, u32), b: &(u32, u32)| a.0.cmp(&b.0))
+            .unwrap();
+        assert!(fs::read_dir(&dir).unwrap().count() <= 4);
+        let res = iter.collect::<io::Result<Vec<(u32, u32)>>>().unwrap();
+        assert_eq!(res, expect);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_stable() {
+        let dir = test_dir("external-sort-stable");
+        let mut rng = thread_rng();
+        let records = (0..5000)
+            .map(|i| (rng.gen_range(0..20), i))
+            .collect::<Vec<(u32, u32)>>();
+        let mut expect = records.clone();
+        expect.sort_by_key(|x| x.0);
+
+        let sorter = ExternalSorter::new(PairCodec, 1000, Some(dir.clone()));
+        let res = sorter
+            .sort_by(records, |a: &(u32, u32), b: &(u32, u32)| a.0.cmp(&b.0))
+            .unwrap()
+            .collect::<io::Result<Vec<(u32, u32)>>>()
+            .unwrap();
+        assert_eq!(res, expect);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_borrowed_cmp() {
+        let dir = test_dir("external-sort-borrowed");
+        let mut rng = thread_rng();
+        let keys = (0..3000).map(|_| rng.gen::<i64>()).collect::<Vec<i64>>();
+        let mut expect = (0..3000).collect::<Vec<u32>>();
+        expect.sort_by_key(|&i| keys[i as usize]);
+
+        // 比较器借用局部变量
+        let sorter = ExternalSorter::new(LeBytesCodec, 400, Some(dir.clone()));
+        let res = sorter
+            .sort_by(0..3000u32, |a, b| keys[*a as usize].cmp(&keys[*b as usize]))
+            .unwrap()
+            .collect::<io::Result<Vec<u32>>>()
+            .unwrap();
+        assert_eq!(res, expect);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_sort_file() {
+        let dir = test_dir("external-sort-file");
+        let input = dir.join("input.txt");
+        let output = dir.join("output.txt");
+        fs::write(&input, "pear\napple\nfig\nbanana\ncherry\napple\n").unwrap();
+
+        let sorter = ExternalSorter::new(LineCodec, 64, Some(dir.clone()));
+        sorter.sort_file::<String>(&input, &output).unwrap();
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            "apple\napple\nbanana\ncherry\nfig\npear\n"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,39 +1,160 @@
-pub fn heapfiy<T>(nums: &mut Vec<T>, start: usize, end: usize)
+use std::cmp::Ordering;
+
+use super::network::{network_sort_slice_by, MAX_NETWORK_SIZE};
+
+// 以下均为 D 叉堆，堆顶是 cmp 意义下最大的元素（与 C++ 的 make_heap 等一致）
+
+pub fn sift_down_by<const D: usize, T, F>(heap: &mut [T], mut pos: usize, cmp: &mut F)
 where
-    T: PartialOrd,
+    F: FnMut(&T, &T) -> Ordering,
 {
-    let mut far = start;
-    let mut child = far * 2 + 1;
-    while child <= end {
-        if child < end && nums[child] < nums[child + 1] {
-            child += 1;
+    const { assert!(D >= 2) };
+    let len = heap.len();
+    loop {
+        let first = pos * D + 1;
+        if first >= len {
+            break;
+        }
+        let mut child = first;
+        for c in first + 1..(first + D).min(len) {
+            if cmp(&heap[child], &heap[c]) == Ordering::Less {
+                child = c;
+            }
         }
-        if nums[child] < nums[far] {
+        if cmp(&heap[pos], &heap[child]) != Ordering::Less {
             break;
         }
-        nums.swap(far, child);
-        far = child;
-        child = far * 2 + 1;
+        heap.swap(pos, child);
+        pos = child;
     }
 }
 
-pub fn heapsort<T>(nums: &mut Vec<T>)
+pub fn sift_up_by<const D: usize, T, F>(heap: &mut [T], mut pos: usize, cmp: &mut F)
 where
-    T: PartialOrd,
+    F: FnMut(&T, &T) -> Ordering,
 {
-    for i in (0..=(nums.len() / 2 - 1)).rev() {
-        heapfiy(nums, i, nums.len() - 1);
+    const { assert!(D >= 2) };
+    while pos > 0 {
+        let far = (pos - 1) / D;
+        if cmp(&heap[far], &heap[pos]) != Ordering::Less {
+            break;
+        }
+        heap.swap(far, pos);
+        pos = far;
     }
+}
+
+pub fn make_heap_by<const D: usize, T, F>(nums: &mut [T], mut cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if nums.len() < 2 {
+        return;
+    }
+    for i in (0..=(nums.len() - 2) / D).rev() {
+        sift_down_by::<D, T, F>(nums, i, &mut cmp);
+    }
+}
+
+// nums[..len - 1] 是堆，把最后一个元素加入堆中
+pub fn push_heap_by<const D: usize, T, F>(nums: &mut [T], mut cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if let Some(last) = nums.len().checked_sub(1) {
+        sift_up_by::<D, T, F>(nums, last, &mut cmp);
+    }
+}
+
+// 把堆顶移到末尾，nums[..len - 1] 仍是堆
+pub fn pop_heap_by<const D: usize, T, F>(nums: &mut [T], mut cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = nums.len();
+    if len < 2 {
+        return;
+    }
+    nums.swap(0, len - 1);
+    sift_down_by::<D, T, F>(&mut nums[..len - 1], 0, &mut cmp);
+}
+
+// nums 是堆，排序后为 cmp 意义下的升序
+pub fn sort_heap_by<const D: usize, T, F>(nums: &mut [T], mut cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for end in (1..nums.len()).rev() {
+        nums.swap(0, end);
+        sift_down_by::<D, T, F>(&mut nums[..end], 0, &mut cmp);
+    }
+}
+
+pub fn is_heap_by<const D: usize, T, F>(nums: &[T], mut cmp: F) -> bool
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    (1..nums.len()).all(|i| cmp(&nums[(i - 1) / D], &nums[i]) != Ordering::Less)
+}
+
+pub fn make_heap<const D: usize, T: Ord>(nums: &mut [T]) {
+    make_heap_by::<D, T, _>(nums, T::cmp);
+}
+
+pub fn push_heap<const D: usize, T: Ord>(nums: &mut [T]) {
+    push_heap_by::<D, T, _>(nums, T::cmp);
+}
+
+pub fn pop_heap<const D: usize, T: Ord>(nums: &mut [T]) {
+    pop_heap_by::<D, T, _>(nums, T::cmp);
+}
+
+pub fn sort_heap<const D: usize, T: Ord>(nums: &mut [T]) {
+    sort_heap_by::<D, T, _>(nums, T::cmp);
+}
+
+pub fn is_heap<const D: usize, T: Ord>(nums: &[T]) -> bool {
+    is_heap_by::<D, T, _>(nums, T::cmp)
+}
 
-    for i in (1..=(nums.len() - 1)).rev() {
-        nums.swap(0, i);
-        heapfiy(nums, 0, i - 1);
+pub fn heapsort_d_by<const D: usize, T, F>(nums: &mut [T], mut cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if nums.len() <= MAX_NETWORK_SIZE {
+        network_sort_slice_by(nums, &mut cmp);
+        return;
     }
+    make_heap_by::<D, T, _>(nums, &mut cmp);
+    sort_heap_by::<D, T, _>(nums, &mut cmp);
+}
+
+pub fn heapsort_d<const D: usize, T>(nums: &mut [T])
+where
+    T: PartialOrd,
+{
+    heapsort_d_by::<D, T, _>(nums, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+pub fn heapsort_by<T, F>(nums: &mut [T], cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    heapsort_d_by::<2, T, F>(nums, cmp);
+}
+
+pub fn heapsort<T>(nums: &mut [T])
+where
+    T: PartialOrd,
+{
+    heapsort_d::<2, T>(nums);
 }
 
 #[cfg(test)]
 mod test {
-    use super::heapsort;
+    use super::*;
+    use rand::{thread_rng, Rng};
+
     #[test]
     fn test1() {
         let mut nums = vec![3, 1, 2, 5, 6];
@@ -47,4 +168,87 @@ mod test {
         heapsort(&mut nums);
         assert_eq!(nums, [1, 23, 217, 219, 232]);
     }
+
+    #[test]
+    fn test_large() {
+        let mut nums = (0..100).map(|x| (x * 37) % 100).collect::<Vec<i32>>();
+        heapsort(&mut nums);
+        assert_eq!(nums, (0..100).collect::<Vec<i32>>());
+    }
+
+    #[test]
+    fn test_by() {
+        let mut nums = vec![217, 219, 1, 23, 232];
+        heapsort_by(&mut nums, |a, b| b.cmp(a));
+        assert_eq!(nums, [232, 219, 217, 23, 1]);
+    }
+
+    #[test]
+    fn test_small() {
+        let mut nums: Vec<i32> = vec![];
+        heapsort(&mut nums);
+        make_heap::<2, i32>(&mut nums);
+        sort_heap::<2, i32>(&mut nums);
+        pop_heap::<2, i32>(&mut nums);
+        assert!(nums.is_empty());
+
+        let mut nums = vec![1];
+        heapsort(&mut nums);
+        make_heap::<3, i32>(&mut nums);
+        sort_heap::<3, i32>(&mut nums);
+        assert_eq!(nums, [1]);
+    }
+
+    fn check_d<const D: usize>() {
+        let mut rng = thread_rng();
+        for n in [0, 1, 2, 3, 40, 1000] {
+            let mut nums = (0..n).map(|_| rng.gen_range(0..100)).collect::<Vec<i32>>();
+            let mut expect = nums.clone();
+            expect.sort();
+
+            let mut heap = nums.clone();
+            make_heap::<D, i32>(&mut heap);
+            assert!(is_heap::<D, i32>(&heap));
+            sort_heap::<D, i32>(&mut heap);
+            assert_eq!(heap, expect);
+
+            heapsort_d::<D, i32>(&mut nums);
+            assert_eq!(nums, expect);
+        }
+    }
+
+    #[test]
+    fn test_d_ary() {
+        check_d::<2>();
+        check_d::<3>();
+        check_d::<4>();
+        check_d::<8>();
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let mut rng = thread_rng();
+        let mut heap = vec![];
+        let mut expect = vec![];
+        for _ in 0..500 {
+            let x = rng.gen_range(-50..50);
+            heap.push(x);
+            push_heap::<4, i32>(&mut heap);
+            expect.push(x);
+            assert!(is_heap::<4, i32>(&heap));
+        }
+        expect.sort();
+        while let Some(&top) = heap.first() {
+            pop_heap::<4, i32>(&mut heap);
+            assert_eq!(heap.pop(), Some(top));
+            assert_eq!(Some(top), expect.pop());
+            assert!(is_heap::<4, i32>(&heap));
+        }
+        assert!(expect.is_empty());
+
+        // 小根堆
+        let mut heap = vec![5, 3, 9, 1];
+        make_heap_by::<2, i32, _>(&mut heap, |a, b| b.cmp(a));
+        assert_eq!(heap[0], 1);
+    }
 }
@@ -0,0 +1,163 @@
+use std::cmp::Ordering;
+
+use super::heapsort::sift_down_by;
+
+// 惰性地合并多个有序迭代器，相等的元素按来源顺序输出
+pub struct MergeK<I: Iterator, F> {
+    sources: Vec<I>,
+    // (当前元素, 来源下标)，堆顶为最小元素
+    heap: Vec<(I::Item, usize)>,
+    cmp: F,
+    dedup: bool,
+}
+
+impl<I, F> MergeK<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    fn new(sources: Vec<I>, cmp: F) -> Self {
+        let mut res = MergeK {
+            sources,
+            heap: vec![],
+            cmp,
+            dedup: false,
+        };
+        for (idx, source) in res.sources.iter_mut().enumerate() {
+            if let Some(item) = source.next() {
+                res.heap.push((item, idx));
+            }
+        }
+        for i in (0..res.heap.len() / 2).rev() {
+            res.sift_down(i);
+        }
+        res
+    }
+
+    // 去掉相等的元素，只保留来源下标最小的一个
+    pub fn dedup(mut self) -> Self {
+        self.dedup = true;
+        self
+    }
+
+    fn sift_down(&mut self, start: usize) {
+        let cmp = &mut self.cmp;
+        sift_down_by::<2, _, _>(&mut self.heap, start, &mut |a: &(I::Item, usize), b| {
+            cmp(&b.0, &a.0).then(b.1.cmp(&a.1))
+        });
+    }
+
+    // 用堆顶元素来源的下一个元素替换堆顶，返回原堆顶
+    fn advance_top(&mut self) -> I::Item {
+        let idx = self.heap[0].1;
+        let item = match self.sources[idx].next() {
+            Some(next) => std::mem::replace(&mut self.heap[0], (next, idx)).0,
+            None => self.heap.swap_remove(0).0,
+        };
+        self.sift_down(0);
+        item
+    }
+}
+
+impl<I, F> Iterator for MergeK<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.heap.is_empty() {
+            return None;
+        }
+        let item = self.advance_top();
+        if self.dedup {
+            while !self.heap.is_empty() && (self.cmp)(&self.heap[0].0, &item) == Ordering::Equal {
+                self.advance_top();
+            }
+        }
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let mut lower = self.heap.len();
+        let mut upper = Some(self.heap.len());
+        for source in &self.sources {
+            let (lo, hi) = source.size_hint();
+            lower = lower.saturating_add(lo);
+            upper = upper.zip(hi).and_then(|(a, b)| a.checked_add(b));
+        }
+        if self.dedup {
+            lower = lower.min(1);
+        }
+        (lower, upper)
+    }
+}
+
+type OrdCmp<T> = fn(&T, &T) -> Ordering;
+
+pub fn merge_k_by<S, I, F>(sources: S, cmp: F) -> MergeK<I::IntoIter, F>
+where
+    S: IntoIterator<Item = I>,
+    I: IntoIterator,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    MergeK::new(sources.into_iter().map(|x| x.into_iter()).collect(), cmp)
+}
+
+pub fn merge_k<S, I>(sources: S) -> MergeK<I::IntoIter, OrdCmp<I::Item>>
+where
+    S: IntoIterator<Item = I>,
+    I: IntoIterator,
+    I::Item: Ord,
+{
+    merge_k_by(sources, Ord::cmp)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::data_structure::skip_list::SkipList;
+
+    #[test]
+    fn test1() {
+        let res = merge_k(vec![vec![1, 4, 7], vec![2, 5, 8], vec![], vec![3, 6, 9]]);
+        assert_eq!(res.collect::<Vec<i32>>(), (1..10).collect::<Vec<i32>>());
+
+        let res = merge_k(Vec::<Vec<i32>>::new());
+        assert_eq!(res.count(), 0);
+    }
+
+    #[test]
+    fn test_dedup() {
+        let sources = vec![vec![1, 1, 3, 5], vec![1, 2, 3], vec![5, 5, 6]];
+        let res = merge_k(sources.clone()).dedup().collect::<Vec<i32>>();
+        assert_eq!(res, [1, 2, 3, 5, 6]);
+        assert_eq!(merge_k(sources).count(), 10);
+    }
+
+    #[test]
+    fn test_by() {
+        // 降序，键相同时按来源顺序
+        let a = vec![(3, 'a'), (1, 'a')];
+        let b = vec![(3, 'b'), (2, 'b')];
+        let res = merge_k_by([a, b], |x, y| y.0.cmp(&x.0)).collect::<Vec<(i32, char)>>();
+        assert_eq!(res, [(3, 'a'), (3, 'b'), (2, 'b'), (1, 'a')]);
+    }
+
+    #[test]
+    fn test_skip_list() {
+        let mut lists = vec![];
+        for i in 0..4 {
+            let mut list = SkipList::<i32>::new(Some(16));
+            for x in (i..100).step_by(4) {
+                list.insert(x);
+            }
+            lists.push(list);
+        }
+        let res = merge_k(lists.iter().map(|x| x.iter()))
+            .copied()
+            .collect::<Vec<i32>>();
+        assert_eq!(res, (0..100).collect::<Vec<i32>>());
+    }
+}
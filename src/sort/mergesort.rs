@@ -0,0 +1,213 @@
+use std::cmp::Ordering;
+
+const INSERTION_THRESHOLD: usize = 16;
+
+// 稳定的插入排序
+fn insertion_sort<T, F>(nums: &mut [T], cmp: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in 1..nums.len() {
+        let mut j = i;
+        while j > 0 && cmp(&nums[j], &nums[j - 1]) == Ordering::Less {
+            nums.swap(j, j - 1);
+            j -= 1;
+        }
+    }
+}
+
+// 合并 nums[..mid] 与 nums[mid..]，左半部分拷贝到 buf 中
+pub(super) fn merge<T, F>(nums: &mut [T], mid: usize, buf: &mut Vec<T>, cmp: &mut F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if mid == 0 || mid == nums.len() || cmp(&nums[mid], &nums[mid - 1]) != Ordering::Less {
+        return;
+    }
+    buf.clear();
+    buf.extend_from_slice(&nums[..mid]);
+
+    let (mut i, mut j, mut k) = (0, mid, 0);
+    while i < buf.len() && j < nums.len() {
+        // 相等时取左边的元素，保证稳定
+        if cmp(&nums[j], &buf[i]) == Ordering::Less {
+            nums.swap(k, j);
+            j += 1;
+        } else {
+            std::mem::swap(&mut nums[k], &mut buf[i]);
+            i += 1;
+        }
+        k += 1;
+    }
+    while i < buf.len() {
+        std::mem::swap(&mut nums[k], &mut buf[i]);
+        i += 1;
+        k += 1;
+    }
+}
+
+// 可复用缓冲区的归并排序，多次排序时避免重复分配
+pub struct MergeSorter<T> {
+    buf: Vec<T>,
+}
+
+impl<T> MergeSorter<T>
+where
+    T: Clone,
+{
+    pub fn new() -> Self {
+        MergeSorter { buf: vec![] }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        MergeSorter {
+            buf: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub(super) fn _sort<F>(&mut self, nums: &mut [T], cmp: &mut F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        if nums.len() <= INSERTION_THRESHOLD {
+            insertion_sort(nums, cmp);
+            return;
+        }
+        let mid = nums.len() / 2;
+        self._sort(&mut nums[..mid], cmp);
+        self._sort(&mut nums[mid..], cmp);
+        merge(nums, mid, &mut self.buf, cmp);
+    }
+
+    // 自顶向下
+    pub fn sort_by<F>(&mut self, nums: &mut [T], mut cmp: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self._sort(nums, &mut cmp);
+        self.buf.clear();
+    }
+
+    // 自底向上
+    pub fn sort_bottom_up_by<F>(&mut self, nums: &mut [T], mut cmp: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let len = nums.len();
+        for chunk in nums.chunks_mut(INSERTION_THRESHOLD) {
+            insertion_sort(chunk, &mut cmp);
+        }
+        let mut width = INSERTION_THRESHOLD;
+        while width < len {
+            let mut lo = 0;
+            while lo + width < len {
+                let hi = (lo + width * 2).min(len);
+                merge(&mut nums[lo..hi], width, &mut self.buf, &mut cmp);
+                lo = hi;
+            }
+            width *= 2;
+        }
+        self.buf.clear();
+    }
+}
+
+impl<T: Clone> Default for MergeSorter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn merge_sort_by<T, F>(nums: &mut [T], cmp: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    MergeSorter::with_capacity(nums.len() / 2).sort_by(nums, cmp);
+}
+
+pub fn merge_sort_by_key<T, K, F>(nums: &mut [T], mut f: F)
+where
+    T: Clone,
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    merge_sort_by(nums, |a, b| f(a).cmp(&f(b)));
+}
+
+pub fn merge_sort<T: Ord + Clone>(nums: &mut [T]) {
+    merge_sort_by(nums, |a, b| a.cmp(b));
+}
+
+pub fn merge_sort_bottom_up_by<T, F>(nums: &mut [T], cmp: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    MergeSorter::with_capacity(nums.len() / 2).sort_bottom_up_by(nums, cmp);
+}
+
+pub fn merge_sort_bottom_up<T: Ord + Clone>(nums: &mut [T]) {
+    merge_sort_bottom_up_by(nums, |a, b| a.cmp(b));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn test1() {
+        let mut nums = vec![3, 4, 1, 5, 2];
+        merge_sort(&mut nums);
+        assert_eq!(nums, [1, 2, 3, 4, 5]);
+
+        let mut nums = vec![3, 4, 1, 5, 2];
+        merge_sort_bottom_up(&mut nums);
+        assert_eq!(nums, [1, 2, 3, 4, 5]);
+
+        let mut nums: Vec<i32> = vec![];
+        merge_sort(&mut nums);
+        merge_sort_bottom_up(&mut nums);
+        assert!(nums.is_empty());
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = thread_rng();
+        let mut sorter = MergeSorter::new();
+        for n in [1, 17, 100, 1000, 12345] {
+            let nums = (0..n)
+                .map(|_| rng.gen_range(-100..100))
+                .collect::<Vec<i32>>();
+            let mut expect = nums.clone();
+            expect.sort();
+
+            let mut a = nums.clone();
+            sorter.sort_by(&mut a, |x, y| x.cmp(y));
+            assert_eq!(a, expect);
+
+            let mut b = nums.clone();
+            sorter.sort_bottom_up_by(&mut b, |x, y| x.cmp(y));
+            assert_eq!(b, expect);
+        }
+    }
+
+    #[test]
+    fn test_stable() {
+        let mut rng = thread_rng();
+        let nums = (0..5000)
+            .map(|i| (rng.gen_range(0..10), i))
+            .collect::<Vec<(i32, i32)>>();
+        let mut expect = nums.clone();
+        expect.sort_by_key(|x| x.0);
+
+        let mut a = nums.clone();
+        merge_sort_by_key(&mut a, |x| x.0);
+        assert_eq!(a, expect);
+
+        let mut b = nums.clone();
+        merge_sort_bottom_up_by(&mut b, |x, y| x.0.cmp(&y.0));
+        assert_eq!(b, expect);
+    }
+}
@@ -1,3 +1,13 @@
+pub mod external;
 pub mod heapsort;
+pub mod merge_k;
+pub mod mergesort;
+pub mod network;
+pub mod parallel;
 pub mod quicksort;
+pub mod radix;
+pub mod stats;
+pub mod stream_topk;
+pub mod timsort;
 pub mod topk;
+pub mod verify;
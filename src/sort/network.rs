@@ -0,0 +1,373 @@
+use std::cmp::Ordering;
+
+pub const MAX_NETWORK_SIZE: usize = 32;
+// 两个 16 元素网络加奇偶归并，n = 32 时的比较器个数
+const MAX_COMPARATORS: usize = 185;
+
+type Network = ([(u8, u8); MAX_COMPARATORS], usize);
+
+// 2..=20 为目前已知最优的网络，见 https://bertdobbelaere.github.io/sorting_networks.html
+// 11、14、15 由更大的网络去掉多余的线得到
+#[rustfmt::skip]
+const BEST: [&[(u8, u8)]; 21] = [
+    &[],
+    &[],
+    &[(0, 1)],
+    &[(0, 2), (0, 1), (1, 2)],
+    &[(0, 2), (1, 3), (0, 1), (2, 3), (1, 2)],
+    &[
+        (0, 3), (1, 4), (0, 2), (1, 3), (0, 1), (2, 4), (1, 2), (3, 4), (2, 3),
+    ],
+    &[
+        (0, 5), (1, 3), (2, 4), (1, 2), (3, 4), (0, 3), (2, 5), (0, 1), (2, 3), (4, 5), (1, 2),
+        (3, 4),
+    ],
+    &[
+        (0, 6), (2, 3), (4, 5), (0, 2), (1, 4), (3, 6), (0, 1), (2, 5), (3, 4), (1, 2), (4, 6),
+        (2, 3), (4, 5), (1, 2), (3, 4), (5, 6),
+    ],
+    &[
+        (0, 2), (1, 3), (4, 6), (5, 7), (0, 4), (1, 5), (2, 6), (3, 7), (0, 1), (2, 3), (4, 5),
+        (6, 7), (2, 4), (3, 5), (1, 4), (3, 6), (1, 2), (3, 4), (5, 6),
+    ],
+    &[
+        (0, 3), (1, 7), (2, 5), (4, 8), (0, 7), (2, 4), (3, 8), (5, 6), (0, 2), (1, 3), (4, 5),
+        (7, 8), (1, 4), (3, 6), (5, 7), (0, 1), (2, 4), (3, 5), (6, 8), (2, 3), (4, 5), (6, 7),
+        (1, 2), (3, 4), (5, 6),
+    ],
+    &[
+        (0, 8), (1, 9), (2, 7), (3, 5), (4, 6), (0, 2), (1, 4), (5, 8), (7, 9), (0, 3), (2, 4),
+        (5, 7), (6, 9), (0, 1), (3, 6), (8, 9), (1, 5), (2, 3), (4, 8), (6, 7), (1, 2), (3, 5),
+        (4, 6), (7, 8), (2, 3), (4, 5), (6, 7), (3, 4), (5, 6),
+    ],
+    &BEST_12,
+    &BEST_12,
+    &BEST_13,
+    &GREEN_16,
+    &GREEN_16,
+    &GREEN_16,
+    &BEST_17,
+    &BEST_18,
+    &BEST_19,
+    &BEST_20,
+];
+
+#[rustfmt::skip]
+const BEST_12: [(u8, u8); 39] = [
+    (0, 8), (1, 7), (2, 6), (3, 11), (4, 10), (5, 9), (0, 1), (2, 5), (3, 4), (6, 9), (7, 8),
+    (10, 11), (0, 2), (1, 6), (5, 10), (9, 11), (0, 3), (1, 2), (4, 6), (5, 7), (8, 11), (9, 10),
+    (1, 4), (3, 5), (6, 8), (7, 10), (1, 3), (2, 5), (6, 9), (8, 10), (2, 3), (4, 5), (6, 7),
+    (8, 9), (4, 6), (5, 7), (3, 4), (5, 6), (7, 8),
+];
+
+#[rustfmt::skip]
+const BEST_13: [(u8, u8); 45] = [
+    (0, 12), (1, 10), (2, 9), (3, 7), (5, 11), (6, 8), (1, 6), (2, 3), (4, 11), (7, 9), (8, 10),
+    (0, 4), (1, 2), (3, 6), (7, 8), (9, 10), (11, 12), (4, 6), (5, 9), (8, 11), (10, 12), (0, 5),
+    (3, 8), (4, 7), (6, 11), (9, 10), (0, 1), (2, 5), (6, 9), (7, 8), (10, 11), (1, 3), (2, 4),
+    (5, 6), (9, 10), (1, 2), (3, 4), (5, 7), (6, 8), (2, 3), (4, 5), (6, 7), (8, 9), (3, 4), (5, 6),
+];
+
+#[rustfmt::skip]
+const BEST_17: [(u8, u8); 71] = [
+    (0, 11), (1, 15), (2, 10), (3, 5), (4, 6), (8, 12), (9, 16), (13, 14), (0, 6), (1, 13), (2, 8),
+    (4, 14), (5, 15), (7, 11), (0, 8), (3, 7), (4, 9), (6, 16), (10, 11), (12, 14), (0, 2), (1, 4),
+    (5, 6), (7, 13), (8, 9), (10, 12), (11, 14), (15, 16), (0, 3), (2, 5), (6, 11), (7, 10),
+    (9, 13), (12, 15), (14, 16), (0, 1), (3, 4), (5, 10), (6, 9), (7, 8), (11, 15), (13, 14),
+    (1, 2), (3, 7), (4, 8), (6, 12), (11, 13), (14, 15), (1, 3), (2, 7), (4, 5), (9, 11), (10, 12),
+    (13, 14), (2, 3), (4, 6), (5, 7), (8, 10), (3, 4), (6, 8), (7, 9), (10, 12), (5, 6), (7, 8),
+    (9, 10), (11, 12), (4, 5), (6, 7), (8, 9), (10, 11), (12, 13),
+];
+
+#[rustfmt::skip]
+const BEST_18: [(u8, u8); 77] = [
+    (0, 1), (2, 3), (4, 5), (6, 7), (8, 9), (10, 11), (12, 13), (14, 15), (16, 17), (1, 5), (2, 6),
+    (3, 7), (4, 10), (8, 16), (9, 17), (12, 14), (13, 15), (0, 8), (1, 10), (2, 12), (3, 14),
+    (6, 13), (7, 15), (9, 16), (11, 17), (0, 4), (1, 9), (5, 17), (8, 11), (10, 16), (0, 2), (1, 6),
+    (4, 10), (5, 9), (14, 16), (15, 17), (1, 2), (3, 10), (4, 12), (5, 7), (6, 14), (9, 13),
+    (15, 16), (3, 8), (5, 12), (7, 11), (9, 10), (3, 4), (6, 8), (7, 14), (9, 12), (11, 13), (1, 3),
+    (2, 4), (7, 9), (8, 12), (11, 15), (13, 16), (2, 3), (4, 5), (6, 7), (10, 11), (12, 14),
+    (13, 15), (4, 6), (5, 8), (9, 10), (11, 14), (3, 4), (5, 7), (8, 9), (10, 12), (13, 14), (5, 6),
+    (7, 8), (9, 10), (11, 12),
+];
+
+#[rustfmt::skip]
+const BEST_19: [(u8, u8); 85] = [
+    (0, 12), (1, 4), (2, 8), (3, 5), (6, 17), (7, 11), (9, 14), (10, 13), (15, 16), (0, 2), (1, 7),
+    (3, 6), (4, 11), (5, 17), (8, 12), (10, 15), (13, 16), (14, 18), (3, 10), (4, 14), (5, 15),
+    (6, 13), (7, 9), (11, 17), (16, 18), (0, 7), (1, 10), (4, 6), (9, 15), (11, 16), (12, 17),
+    (13, 14), (0, 3), (2, 6), (5, 7), (8, 11), (12, 16), (1, 8), (2, 9), (3, 4), (6, 15), (7, 13),
+    (10, 11), (12, 18), (1, 3), (2, 5), (6, 9), (7, 12), (8, 10), (11, 14), (17, 18), (0, 1),
+    (2, 3), (4, 8), (6, 10), (9, 12), (14, 15), (16, 17), (1, 2), (5, 8), (6, 7), (9, 11), (10, 13),
+    (14, 16), (15, 17), (3, 6), (4, 5), (7, 9), (8, 10), (11, 12), (13, 14), (15, 16), (3, 4),
+    (5, 6), (7, 8), (9, 10), (11, 13), (12, 14), (2, 3), (4, 5), (6, 7), (8, 9), (10, 11), (12, 13),
+    (14, 15),
+];
+
+#[rustfmt::skip]
+const BEST_20: [(u8, u8); 91] = [
+    (0, 3), (1, 7), (2, 5), (4, 8), (6, 9), (10, 13), (11, 15), (12, 18), (14, 17), (16, 19),
+    (0, 14), (2, 16), (3, 17), (4, 12), (5, 19), (7, 15), (1, 11), (8, 18), (6, 10), (9, 13),
+    (0, 4), (1, 2), (3, 8), (5, 7), (11, 16), (12, 14), (15, 19), (17, 18), (1, 6), (2, 12), (3, 5),
+    (4, 11), (7, 17), (8, 15), (13, 18), (14, 16), (0, 1), (2, 6), (7, 10), (9, 12), (13, 17),
+    (18, 19), (1, 6), (5, 9), (7, 11), (8, 12), (10, 14), (13, 18), (3, 5), (4, 7), (8, 10),
+    (9, 11), (12, 15), (14, 16), (1, 3), (2, 4), (5, 7), (6, 10), (9, 13), (12, 14), (15, 17),
+    (16, 18), (1, 2), (3, 4), (6, 7), (8, 9), (10, 11), (12, 13), (15, 16), (17, 18), (2, 3),
+    (4, 6), (5, 8), (7, 9), (10, 12), (11, 14), (13, 15), (16, 17), (4, 5), (6, 8), (7, 10),
+    (9, 12), (11, 13), (14, 15), (3, 4), (5, 6), (7, 8), (9, 10), (11, 12), (13, 14), (15, 16),
+];
+
+// Green 的 16 元素网络
+#[rustfmt::skip]
+const GREEN_16: [(u8, u8); 60] = [
+    (0, 13), (1, 12), (2, 15), (3, 14), (4, 8), (5, 6), (7, 11), (9, 10), (0, 5), (1, 7),
+    (2, 9), (3, 4), (6, 13), (8, 14), (10, 15), (11, 12), (0, 1), (2, 3), (4, 5), (6, 8),
+    (7, 9), (10, 11), (12, 13), (14, 15), (0, 2), (1, 3), (4, 10), (5, 11), (6, 7), (8, 9),
+    (12, 14), (13, 15), (1, 2), (3, 12), (4, 6), (5, 7), (8, 10), (9, 11), (13, 14), (1, 4),
+    (2, 6), (5, 8), (7, 10), (9, 13), (11, 14), (2, 4), (3, 6), (9, 12), (11, 13), (3, 5),
+    (6, 8), (7, 9), (10, 12), (3, 4), (5, 6), (7, 8), (9, 10), (11, 12), (6, 7), (8, 9),
+];
+
+// 相当于在末尾补上无穷大，涉及这些位置的比较器不会交换，可以直接去掉
+const fn from_pairs(pairs: &[(u8, u8)], n: usize) -> Network {
+    let mut res = [(0, 0); MAX_COMPARATORS];
+    let mut len = 0;
+    let mut i = 0;
+    while i < pairs.len() {
+        if (pairs[i].1 as usize) < n {
+            res[len] = pairs[i];
+            len += 1;
+        }
+        i += 1;
+    }
+    (res, len)
+}
+
+const fn push(net: &mut Network, i: usize, j: usize) {
+    net.0[net.1] = (i as u8, j as u8);
+    net.1 += 1;
+}
+
+// (起点, 步长, 长度)，描述一段等间隔的位置
+type Run = (usize, usize, usize);
+
+// a 后面接上 b 之后的第 k 个位置
+const fn merge_pos(a: Run, b: Run, k: usize) -> usize {
+    if k < a.2 {
+        a.0 + k * a.1
+    } else {
+        b.0 + (k - a.2) * b.1
+    }
+}
+
+// Batcher 奇偶归并：偶数位和奇数位各自归并，再比较相邻的一对
+const fn odd_even_merge(net: &mut Network, a: Run, b: Run) {
+    let (m, n) = (a.2, b.2);
+    if m == 0 || n == 0 {
+        return;
+    }
+    if m == 1 && n == 1 {
+        push(net, a.0, b.0);
+        return;
+    }
+    odd_even_merge(
+        net,
+        (a.0, a.1 * 2, m.div_ceil(2)),
+        (b.0, b.1 * 2, n.div_ceil(2)),
+    );
+    odd_even_merge(
+        net,
+        (a.0 + a.1, a.1 * 2, m / 2),
+        (b.0 + b.1, b.1 * 2, n / 2),
+    );
+    let mut k = 1;
+    while k + 1 < m + n {
+        push(net, merge_pos(a, b, k), merge_pos(a, b, k + 1));
+        k += 2;
+    }
+}
+
+// odd_even_merge 用到的比较器个数
+const fn merge_size(m: usize, n: usize) -> usize {
+    if m == 0 || n == 0 {
+        0
+    } else if m == 1 && n == 1 {
+        1
+    } else {
+        merge_size(m.div_ceil(2), n.div_ceil(2)) + merge_size(m / 2, n / 2) + (m + n - 1) / 2
+    }
+}
+
+// 21..=32 把两半分别用已知最优的网络排好再归并，取比较器最少的划分
+const fn merged(n: usize) -> Network {
+    let mut best = 0;
+    let mut best_len = usize::MAX;
+    let mut a = n - (BEST.len() - 1);
+    while a < BEST.len() {
+        let len =
+            from_pairs(BEST[a], a).1 + from_pairs(BEST[n - a], n - a).1 + merge_size(a, n - a);
+        if len < best_len {
+            best = a;
+            best_len = len;
+        }
+        a += 1;
+    }
+    let mut res = from_pairs(BEST[best], best);
+    let half = from_pairs(BEST[n - best], n - best);
+    let mut i = 0;
+    while i < half.1 {
+        let (x, y) = half.0[i];
+        push(&mut res, x as usize + best, y as usize + best);
+        i += 1;
+    }
+    odd_even_merge(&mut res, (0, 1, best), (best, 1, n - best));
+    res
+}
+
+const fn build_networks() -> [Network; MAX_NETWORK_SIZE + 1] {
+    let mut res = [([(0, 0); MAX_COMPARATORS], 0); MAX_NETWORK_SIZE + 1];
+    let mut n = 0;
+    while n <= MAX_NETWORK_SIZE {
+        res[n] = if n < BEST.len() {
+            from_pairs(BEST[n], n)
+        } else {
+            merged(n)
+        };
+        n += 1;
+    }
+    res
+}
+
+static NETWORKS: [Network; MAX_NETWORK_SIZE + 1] = build_networks();
+
+fn comparators(n: usize) -> &'static [(u8, u8)] {
+    let (pairs, len) = &NETWORKS[n];
+    &pairs[..*len]
+}
+
+// 不稳定，nums.len() 不能超过 MAX_NETWORK_SIZE
+pub fn network_sort_slice_by<T, F>(nums: &mut [T], cmp: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    assert!(
+        nums.len() <= MAX_NETWORK_SIZE,
+        "slice too long for sorting network"
+    );
+    for &(i, j) in comparators(nums.len()) {
+        let (i, j) = (i as usize, j as usize);
+        if cmp(&nums[j], &nums[i]) == Ordering::Less {
+            nums.swap(i, j);
+        }
+    }
+}
+
+pub fn network_sort_by<T, F, const N: usize>(arr: &mut [T; N], mut cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    const { assert!(N <= MAX_NETWORK_SIZE) };
+    network_sort_slice_by(arr, &mut cmp);
+}
+
+pub fn network_sort<T: Ord, const N: usize>(arr: &mut [T; N]) {
+    network_sort_by(arr, |a, b| a.cmp(b));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn test1() {
+        let mut arr = [3, 1, 2];
+        network_sort(&mut arr);
+        assert_eq!(arr, [1, 2, 3]);
+
+        let mut arr = [5, 9, 1, 3, 7, 2, 8, 6, 4, 0];
+        network_sort_by(&mut arr, |a, b| b.cmp(a));
+        assert_eq!(arr, [9, 8, 7, 6, 5, 4, 3, 2, 1, 0]);
+
+        let mut arr: [i32; 0] = [];
+        network_sort(&mut arr);
+    }
+
+    #[test]
+    fn test_size() {
+        let expect = [
+            0, 0, 1, 3, 5, 9, 12, 16, 19, 25, 29, 35, 39, 45, 51, 56, 60, 71, 77, 85, 91, 103, 110,
+            118, 123, 133, 140, 150, 156, 165, 172, 180, 185,
+        ];
+        for (n, &cnt) in expect.iter().enumerate() {
+            assert_eq!(comparators(n).len(), cnt, "n = {n}");
+        }
+    }
+
+    // 每一位是一组 0/1 输入，与、或一次就模拟了 64 组输入过同一个比较器
+    fn sorts_bits(n: usize, lanes: &mut [u64]) -> bool {
+        for &(i, j) in comparators(n) {
+            let (i, j) = (i as usize, j as usize);
+            let (a, b) = (lanes[i], lanes[j]);
+            lanes[i] = a & b;
+            lanes[j] = a | b;
+        }
+        lanes[..n].windows(2).all(|x| x[0] & !x[1] == 0)
+    }
+
+    // 0-1 原理：能排好所有 0/1 序列的网络就能排好任意序列
+    #[test]
+    fn test_zero_one() {
+        const LOW: [u64; 6] = [
+            0xAAAA_AAAA_AAAA_AAAA,
+            0xCCCC_CCCC_CCCC_CCCC,
+            0xF0F0_F0F0_F0F0_F0F0,
+            0xFF00_FF00_FF00_FF00,
+            0xFFFF_0000_FFFF_0000,
+            0xFFFF_FFFF_0000_0000,
+        ];
+        for n in 2usize..=24 {
+            for block in 0u64..1 << n.saturating_sub(6) {
+                let mut lanes = (0..n)
+                    .map(|i| match i {
+                        0..=5 => LOW[i],
+                        _ => 0u64.wrapping_sub((block >> (i - 6)) & 1),
+                    })
+                    .collect::<Vec<u64>>();
+                assert!(sorts_bits(n, &mut lanes), "n = {n}");
+            }
+        }
+    }
+
+    // 更大的 n 穷举太慢，随机取 0/1 序列，并让 1 的比例有高有低
+    #[test]
+    fn test_zero_one_random() {
+        let mut rng = thread_rng();
+        for n in 25..=MAX_NETWORK_SIZE {
+            for _ in 0..2000 {
+                let mut lanes = (0..n)
+                    .map(|_| match rng.gen_range(0..3) {
+                        0 => rng.gen::<u64>() & rng.gen::<u64>(),
+                        1 => rng.gen::<u64>(),
+                        _ => rng.gen::<u64>() | rng.gen::<u64>(),
+                    })
+                    .collect::<Vec<u64>>();
+                assert!(sorts_bits(n, &mut lanes), "n = {n}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = thread_rng();
+        for n in 0..=MAX_NETWORK_SIZE {
+            for _ in 0..200 {
+                let mut nums = (0..n).map(|_| rng.gen_range(0..20)).collect::<Vec<i32>>();
+                let mut expect = nums.clone();
+                expect.sort();
+                network_sort_slice_by(&mut nums, &mut |a: &i32, b: &i32| a.cmp(b));
+                assert_eq!(nums, expect);
+            }
+        }
+    }
+}
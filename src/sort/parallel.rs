@@ -0,0 +1,145 @@
+use std::cmp::Ordering;
+use std::thread;
+
+use super::mergesort::{merge, MergeSorter};
+use super::quicksort::{self, choose_pivot, depth_limit, partition, SMALL_SORT_THRESHOLD};
+
+// 每个线程分到的区间不小于 cutoff 时才继续拆分
+pub struct ParallelSorter {
+    threads: usize,
+    cutoff: usize,
+}
+
+impl ParallelSorter {
+    pub fn new(threads: Option<usize>, cutoff: Option<usize>) -> Self {
+        let threads = threads
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, |x| x.get()))
+            .max(1);
+        ParallelSorter {
+            threads,
+            cutoff: cutoff.unwrap_or(1 << 13).max(SMALL_SORT_THRESHOLD),
+        }
+    }
+
+    fn _merge_sort<T, F>(&self, nums: &mut [T], cmp: &F, threads: usize)
+    where
+        T: Clone + Send,
+        F: Fn(&T, &T) -> Ordering + Sync,
+    {
+        if threads <= 1 || nums.len() <= self.cutoff {
+            MergeSorter::new()._sort(nums, &mut |a: &T, b: &T| cmp(a, b));
+            return;
+        }
+        let mid = nums.len() / 2;
+        let (left, right) = nums.split_at_mut(mid);
+        thread::scope(|s| {
+            s.spawn(|| self._merge_sort(left, cmp, threads / 2));
+            self._merge_sort(right, cmp, threads - threads / 2);
+        });
+        merge(nums, mid, &mut vec![], &mut |a: &T, b: &T| cmp(a, b));
+    }
+
+    pub fn merge_sort_by<T, F>(&self, nums: &mut [T], cmp: F)
+    where
+        T: Clone + Send,
+        F: Fn(&T, &T) -> Ordering + Sync,
+    {
+        self._merge_sort(nums, &cmp, self.threads);
+    }
+
+    // 与 quicksort::_sort 选取相同的枢轴和划分，结果与串行版本完全一致
+    fn _quick_sort<T, F>(&self, nums: &mut [T], cmp: &F, limit: usize, threads: usize)
+    where
+        T: Send,
+        F: Fn(&T, &T) -> Ordering + Sync,
+    {
+        let mut seq_cmp = |a: &T, b: &T| cmp(a, b);
+        if threads <= 1 || nums.len() <= self.cutoff || limit == 0 {
+            quicksort::_sort(nums, &mut seq_cmp, limit);
+            return;
+        }
+        let pivot = choose_pivot(nums, &mut seq_cmp);
+        let (lt, gt) = partition(nums, pivot, &mut seq_cmp);
+        let (left, rest) = nums.split_at_mut(lt);
+        let right = &mut rest[gt - lt..];
+        thread::scope(|s| {
+            s.spawn(|| self._quick_sort(left, cmp, limit - 1, threads / 2));
+            self._quick_sort(right, cmp, limit - 1, threads - threads / 2);
+        });
+    }
+
+    pub fn quick_sort_by<T, F>(&self, nums: &mut [T], cmp: F)
+    where
+        T: Send,
+        F: Fn(&T, &T) -> Ordering + Sync,
+    {
+        if nums.len() < 2 {
+            return;
+        }
+        self._quick_sort(nums, &cmp, depth_limit(nums.len()), self.threads);
+    }
+}
+
+impl Default for ParallelSorter {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+pub fn par_merge_sort<T: Ord + Clone + Send>(nums: &mut [T]) {
+    ParallelSorter::default().merge_sort_by(nums, |a, b| a.cmp(b));
+}
+
+pub fn par_quick_sort<T: Ord + Send>(nums: &mut [T]) {
+    ParallelSorter::default().quick_sort_by(nums, |a, b| a.cmp(b));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sort::mergesort::merge_sort_by;
+    use crate::sort::quicksort::quick_sort_by;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn test1() {
+        let mut nums = vec![3, 4, 1, 5, 2];
+        par_merge_sort(&mut nums);
+        assert_eq!(nums, [1, 2, 3, 4, 5]);
+
+        let mut nums = vec![3, 4, 1, 5, 2];
+        par_quick_sort(&mut nums);
+        assert_eq!(nums, [1, 2, 3, 4, 5]);
+
+        let mut nums: Vec<i32> = vec![];
+        par_quick_sort(&mut nums);
+        par_merge_sort(&mut nums);
+        assert!(nums.is_empty());
+    }
+
+    #[test]
+    fn test_same_as_sequential() {
+        let mut rng = thread_rng();
+        let nums = (0..200000)
+            .map(|i| (rng.gen_range(0..1000), i))
+            .collect::<Vec<(i32, i32)>>();
+        let cmp = |a: &(i32, i32), b: &(i32, i32)| a.0.cmp(&b.0);
+
+        let mut seq_merge = nums.clone();
+        merge_sort_by(&mut seq_merge, cmp);
+        let mut seq_quick = nums.clone();
+        quick_sort_by(&mut seq_quick, cmp);
+
+        for threads in [1, 2, 3, 8] {
+            let sorter = ParallelSorter::new(Some(threads), Some(1000));
+
+            let mut a = nums.clone();
+            sorter.merge_sort_by(&mut a, cmp);
+            assert_eq!(a, seq_merge);
+
+            let mut b = nums.clone();
+            sorter.quick_sort_by(&mut b, cmp);
+            assert_eq!(b, seq_quick);
+        }
+    }
+}
@@ -1,33 +1,139 @@
-use std::cmp::Ord;
-
-fn partition<T: Ord>(nums: &mut Vec<T>, lo: usize, hi: usize) -> usize {
-    let pivot = hi;
-    let mut i = lo;
-    for j in lo..=hi {
-        if nums[j] < nums[pivot] {
-            nums.swap(i, j);
-            i += 1;
+use std::cmp::Ordering;
+
+use super::heapsort::heapsort_by;
+use super::network::network_sort_slice_by;
+
+// 不超过该长度的区间直接用排序网络
+pub(super) const SMALL_SORT_THRESHOLD: usize = 16;
+// 大于该长度的区间使用 ninther 选取枢轴
+const NINTHER_THRESHOLD: usize = 128;
+
+fn median_of_three<T, F>(nums: &[T], a: usize, b: usize, c: usize, cmp: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if cmp(&nums[a], &nums[b]) == Ordering::Less {
+        if cmp(&nums[b], &nums[c]) == Ordering::Less {
+            b
+        } else if cmp(&nums[a], &nums[c]) == Ordering::Less {
+            c
+        } else {
+            a
+        }
+    } else if cmp(&nums[a], &nums[c]) == Ordering::Less {
+        a
+    } else if cmp(&nums[b], &nums[c]) == Ordering::Less {
+        c
+    } else {
+        b
+    }
+}
+
+pub(super) fn choose_pivot<T, F>(nums: &[T], cmp: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = nums.len();
+    let mid = len / 2;
+    if len < NINTHER_THRESHOLD {
+        return median_of_three(nums, 0, mid, len - 1, cmp);
+    }
+    let step = len / 8;
+    let a = median_of_three(nums, 0, step, step * 2, cmp);
+    let b = median_of_three(nums, mid - step, mid, mid + step, cmp);
+    let c = median_of_three(nums, len - 1 - step * 2, len - 1 - step, len - 1, cmp);
+    median_of_three(nums, a, b, c, cmp)
+}
+
+// 三路划分，返回 (lt, gt)：
+// nums[..lt] < pivot, nums[lt..gt] == pivot, nums[gt..] > pivot
+pub(super) fn partition<T, F>(nums: &mut [T], pivot: usize, cmp: &mut F) -> (usize, usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    nums.swap(0, pivot);
+    let mut lt = 0;
+    let mut i = 1;
+    let mut gt = nums.len();
+    while i < gt {
+        match cmp(&nums[i], &nums[lt]) {
+            Ordering::Less => {
+                nums.swap(lt, i);
+                lt += 1;
+                i += 1;
+            }
+            Ordering::Greater => {
+                gt -= 1;
+                nums.swap(i, gt);
+            }
+            Ordering::Equal => i += 1,
         }
     }
-    nums.swap(i, pivot);
-    i
+    (lt, gt)
+}
+
+pub(super) fn _sort<T, F>(mut nums: &mut [T], cmp: &mut F, mut limit: usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    loop {
+        if nums.len() <= SMALL_SORT_THRESHOLD {
+            network_sort_slice_by(nums, cmp);
+            return;
+        }
+        if limit == 0 {
+            heapsort_by(nums, &mut *cmp);
+            return;
+        }
+        limit -= 1;
+
+        let pivot = choose_pivot(nums, cmp);
+        let (lt, gt) = partition(nums, pivot, cmp);
+        let (left, rest) = std::mem::take(&mut nums).split_at_mut(lt);
+        let right = &mut rest[gt - lt..];
+
+        // 递归较短的一边，循环处理较长的一边，保证栈深度为 O(log n)
+        if left.len() < right.len() {
+            _sort(left, cmp, limit);
+            nums = right;
+        } else {
+            _sort(right, cmp, limit);
+            nums = left;
+        }
+    }
+}
+
+pub(super) fn depth_limit(len: usize) -> usize {
+    2 * (usize::BITS - len.leading_zeros()) as usize
 }
 
-fn _sort<T: Ord>(nums: &mut Vec<T>, lo: usize, hi: usize) {
-    if lo < hi {
-        let m = partition(nums, lo, hi);
-        _sort(nums, lo, m - 1);
-        _sort(nums, m + 1, hi);
+pub fn quick_sort_by<T, F>(nums: &mut [T], mut cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if nums.len() < 2 {
+        return;
     }
+    _sort(nums, &mut cmp, depth_limit(nums.len()));
 }
 
-pub fn quick_sort<T: Ord>(nums: &mut Vec<T>) {
-    _sort(nums, 0, nums.len() - 1);
+pub fn quick_sort_by_key<T, K, F>(nums: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    quick_sort_by(nums, |a, b| f(a).cmp(&f(b)));
+}
+
+pub fn quick_sort<T: Ord>(nums: &mut [T]) {
+    quick_sort_by(nums, |a, b| a.cmp(b));
 }
 
 #[cfg(test)]
 mod test {
-    use super::quick_sort;
+    use super::{_sort, quick_sort, quick_sort_by, quick_sort_by_key};
+    use rand::{thread_rng, Rng};
+
     #[test]
     fn test_sort_1() {
         let mut nums = vec![3, 4, 1, 5, 2];
@@ -41,4 +147,49 @@ mod test {
         quick_sort(&mut nums);
         assert_eq!(nums, [1]);
     }
+
+    #[test]
+    fn test_empty() {
+        let mut nums: Vec<i32> = vec![];
+        quick_sort(&mut nums);
+        assert!(nums.is_empty());
+    }
+
+    #[test]
+    fn test_patterns() {
+        let n = 100000;
+        let sorted = (0..n).collect::<Vec<i32>>();
+        let reversed = (0..n).rev().collect::<Vec<i32>>();
+        let dup = (0..n).map(|x| x % 3).collect::<Vec<i32>>();
+        let saw = (0..n).map(|x| x % 1000).collect::<Vec<i32>>();
+        let mut rng = thread_rng();
+        let random = (0..n)
+            .map(|_| rng.gen_range(-1000..1000))
+            .collect::<Vec<i32>>();
+
+        for mut nums in [sorted, reversed, dup, saw, random] {
+            let mut expect = nums.clone();
+            expect.sort();
+            quick_sort(&mut nums);
+            assert_eq!(nums, expect);
+        }
+    }
+
+    #[test]
+    fn test_sort_by() {
+        let mut nums = vec![3, 4, 1, 5, 2];
+        quick_sort_by(&mut nums, |a, b| b.cmp(a));
+        assert_eq!(nums, [5, 4, 3, 2, 1]);
+
+        let mut words = vec!["ccc", "a", "bb"];
+        quick_sort_by_key(&mut words, |x| x.len());
+        assert_eq!(words, ["a", "bb", "ccc"]);
+    }
+
+    #[test]
+    fn test_heap_fallback() {
+        let mut nums = (0..1000).rev().collect::<Vec<i32>>();
+        _sort(&mut nums, &mut |a: &i32, b: &i32| a.cmp(b), 0);
+        assert_eq!(nums, (0..1000).collect::<Vec<i32>>());
+    }
 }
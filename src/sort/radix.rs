@@ -0,0 +1,318 @@
+// 把键映射为保序的无符号整数，再按字节做计数排序
+pub trait RadixKey: Copy {
+    const BYTES: usize;
+    fn to_radix(self) -> u64;
+}
+
+macro_rules! impl_radix_unsigned {
+    ($($t:ty),*) => {
+        $(impl RadixKey for $t {
+            const BYTES: usize = std::mem::size_of::<$t>();
+            fn to_radix(self) -> u64 {
+                self as u64
+            }
+        })*
+    };
+}
+
+macro_rules! impl_radix_signed {
+    ($($t:ty => $u:ty),*) => {
+        $(impl RadixKey for $t {
+            const BYTES: usize = std::mem::size_of::<$t>();
+            fn to_radix(self) -> u64 {
+                // 翻转符号位，负数排在前面
+                ((self as $u) ^ (1 << (<$u>::BITS - 1))) as u64
+            }
+        })*
+    };
+}
+
+impl_radix_unsigned!(u8, u16, u32, u64, usize);
+impl_radix_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, isize => usize);
+
+impl RadixKey for f32 {
+    const BYTES: usize = 4;
+    fn to_radix(self) -> u64 {
+        // 负数取反全部位，正数只置符号位；NaN 按位模式排在两端
+        let bits = self.to_bits();
+        let mask = if bits >> 31 == 1 { u32::MAX } else { 1 << 31 };
+        (bits ^ mask) as u64
+    }
+}
+
+impl RadixKey for f64 {
+    const BYTES: usize = 8;
+    fn to_radix(self) -> u64 {
+        let bits = self.to_bits();
+        let mask = if bits >> 63 == 1 { u64::MAX } else { 1 << 63 };
+        bits ^ mask
+    }
+}
+
+// perm[i] 表示排序后第 i 个位置的元素在原数组中的下标
+fn apply_permutation<T>(nums: &mut [T], mut perm: Vec<usize>) {
+    for i in 0..nums.len() {
+        let mut cur = i;
+        while perm[cur] != i {
+            let next = perm[cur];
+            nums.swap(cur, next);
+            perm[cur] = cur;
+            cur = next;
+        }
+        perm[cur] = cur;
+    }
+}
+
+pub fn radix_sort_by_key<T, K, F>(nums: &mut [T], mut key: F)
+where
+    K: RadixKey,
+    F: FnMut(&T) -> K,
+{
+    let n = nums.len();
+    if n < 2 {
+        return;
+    }
+    let keys = nums.iter().map(|x| key(x).to_radix()).collect::<Vec<u64>>();
+    let mut idx = (0..n).collect::<Vec<usize>>();
+    let mut tmp = vec![0; n];
+
+    for byte in 0..K::BYTES {
+        let shift = byte * 8;
+        let mut count = [0usize; 257];
+        for &i in &idx {
+            count[((keys[i] >> shift) & 0xff) as usize + 1] += 1;
+        }
+        // 所有元素落在同一个桶中，跳过这一轮
+        if count.contains(&n) {
+            continue;
+        }
+        for b in 0..256 {
+            count[b + 1] += count[b];
+        }
+        for &i in &idx {
+            let b = ((keys[i] >> shift) & 0xff) as usize;
+            tmp[count[b]] = i;
+            count[b] += 1;
+        }
+        std::mem::swap(&mut idx, &mut tmp);
+    }
+    apply_permutation(nums, idx);
+}
+
+pub fn radix_sort<K: RadixKey>(nums: &mut [K]) {
+    radix_sort_by_key(nums, |x| *x);
+}
+
+const MSD_INSERTION_THRESHOLD: usize = 16;
+
+fn msd(keys: &[&[u8]], idx: &mut [usize], tmp: &mut [usize], depth: usize) {
+    if idx.len() <= MSD_INSERTION_THRESHOLD {
+        for i in 1..idx.len() {
+            let mut j = i;
+            while j > 0 && keys[idx[j]][depth..] < keys[idx[j - 1]][depth..] {
+                idx.swap(j, j - 1);
+                j -= 1;
+            }
+        }
+        return;
+    }
+
+    // 桶 0 存放已经结束的串
+    let bucket = |i: usize| keys[i].get(depth).map_or(0, |&b| b as usize + 1);
+    let mut count = [0usize; 258];
+    for &i in idx.iter() {
+        count[bucket(i) + 1] += 1;
+    }
+    for b in 0..257 {
+        count[b + 1] += count[b];
+    }
+    let start = count;
+    for &i in idx.iter() {
+        let b = bucket(i);
+        tmp[count[b]] = i;
+        count[b] += 1;
+    }
+    idx.copy_from_slice(&tmp[..idx.len()]);
+
+    for b in 1..257 {
+        let (lo, hi) = (start[b], start[b + 1]);
+        if hi - lo > 1 {
+            msd(keys, &mut idx[lo..hi], &mut tmp[lo..hi], depth + 1);
+        }
+    }
+}
+
+pub fn msd_radix_sort_by_key<T, F>(nums: &mut [T], key: F)
+where
+    F: Fn(&T) -> &[u8],
+{
+    let n = nums.len();
+    if n < 2 {
+        return;
+    }
+    let mut idx = (0..n).collect::<Vec<usize>>();
+    {
+        let keys = nums.iter().map(&key).collect::<Vec<&[u8]>>();
+        let mut tmp = vec![0; n];
+        msd(&keys, &mut idx, &mut tmp, 0);
+    }
+    apply_permutation(nums, idx);
+}
+
+pub fn msd_radix_sort<T: AsRef<[u8]>>(nums: &mut [T]) {
+    msd_radix_sort_by_key(nums, |x| x.as_ref());
+}
+
+// 取值范围超过元素个数的这么多倍时改用基数排序，避免分配过大的计数数组
+const COUNTING_RANGE_RATIO: usize = 8;
+
+// 适用于键的取值范围较小的情况，稳定
+pub fn counting_sort_by_key<T, F>(nums: &mut [T], mut key: F)
+where
+    F: FnMut(&T) -> i64,
+{
+    let n = nums.len();
+    if n < 2 {
+        return;
+    }
+    let keys = nums.iter().map(&mut key).collect::<Vec<i64>>();
+    let min = *keys.iter().min().unwrap();
+    let max = *keys.iter().max().unwrap();
+    // max - min 可能溢出 i64，用无符号的差值
+    let range = usize::try_from(max.abs_diff(min))
+        .ok()
+        .and_then(|r| r.checked_add(1))
+        .filter(|&r| r <= n.saturating_mul(COUNTING_RANGE_RATIO).max(256));
+    let Some(range) = range else {
+        radix_sort_by_key(nums, key);
+        return;
+    };
+
+    let mut count = vec![0usize; range + 1];
+    for &k in &keys {
+        count[k.abs_diff(min) as usize + 1] += 1;
+    }
+    for i in 0..range {
+        count[i + 1] += count[i];
+    }
+    let mut perm = vec![0; n];
+    for (i, &k) in keys.iter().enumerate() {
+        let b = k.abs_diff(min) as usize;
+        perm[count[b]] = i;
+        count[b] += 1;
+    }
+    apply_permutation(nums, perm);
+}
+
+pub fn counting_sort<T>(nums: &mut [T])
+where
+    T: Copy + Into<i64>,
+{
+    counting_sort_by_key(nums, |x| (*x).into());
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn test_unsigned() {
+        let mut rng = thread_rng();
+        let mut nums = (0..10000).map(|_| rng.gen::<u64>()).collect::<Vec<u64>>();
+        let mut expect = nums.clone();
+        expect.sort();
+        radix_sort(&mut nums);
+        assert_eq!(nums, expect);
+
+        let mut nums: Vec<u8> = vec![];
+        radix_sort(&mut nums);
+        assert!(nums.is_empty());
+    }
+
+    #[test]
+    fn test_signed() {
+        let mut rng = thread_rng();
+        let mut nums = (0..10000).map(|_| rng.gen::<i32>()).collect::<Vec<i32>>();
+        nums.extend([i32::MIN, i32::MAX, 0, -1]);
+        let mut expect = nums.clone();
+        expect.sort();
+        radix_sort(&mut nums);
+        assert_eq!(nums, expect);
+    }
+
+    #[test]
+    fn test_float() {
+        let mut nums = vec![3.5, -0.5, 0.0, -100.25, 1e10, f64::NEG_INFINITY, 2.0];
+        radix_sort(&mut nums);
+        assert_eq!(
+            nums,
+            [f64::NEG_INFINITY, -100.25, -0.5, 0.0, 2.0, 3.5, 1e10]
+        );
+
+        let mut nums = vec![1.5f32, -2.5, 0.25];
+        radix_sort(&mut nums);
+        assert_eq!(nums, [-2.5, 0.25, 1.5]);
+    }
+
+    #[test]
+    fn test_by_key_stable() {
+        let mut rng = thread_rng();
+        let mut nums = (0..5000)
+            .map(|i| (rng.gen_range(-20i16..20), i))
+            .collect::<Vec<(i16, i32)>>();
+        let mut expect = nums.clone();
+        expect.sort_by_key(|x| x.0);
+
+        let mut a = nums.clone();
+        radix_sort_by_key(&mut a, |x| x.0);
+        assert_eq!(a, expect);
+
+        counting_sort_by_key(&mut nums, |x| x.0 as i64);
+        assert_eq!(nums, expect);
+    }
+
+    #[test]
+    fn test_counting() {
+        let mut nums = vec![3u8, 1, 2, 3, 0, 255, 1];
+        counting_sort(&mut nums);
+        assert_eq!(nums, [0, 1, 1, 2, 3, 3, 255]);
+    }
+
+    #[test]
+    fn test_counting_wide_range() {
+        // max - min 超出 i64，退回基数排序
+        let mut nums = vec![i64::MAX, 0, i64::MIN, -1, i64::MAX, 1];
+        counting_sort(&mut nums);
+        assert_eq!(nums, [i64::MIN, -1, 0, 1, i64::MAX, i64::MAX]);
+
+        // 范围远大于元素个数时同样不分配计数数组，且保持稳定
+        let mut nums = vec![(1_000_000_000i64, 'a'), (-5, 'b'), (1_000_000_000, 'c')];
+        counting_sort_by_key(&mut nums, |x| x.0);
+        assert_eq!(
+            nums,
+            [(-5, 'b'), (1_000_000_000, 'a'), (1_000_000_000, 'c')]
+        );
+    }
+
+    #[test]
+    fn test_msd() {
+        let mut words = vec!["banana", "apple", "", "app", "b", "apple", "abc"];
+        msd_radix_sort(&mut words);
+        assert_eq!(words, ["", "abc", "app", "apple", "apple", "b", "banana"]);
+
+        let mut rng = thread_rng();
+        let mut strs = (0..3000)
+            .map(|_| {
+                let len = rng.gen_range(0..8);
+                (0..len)
+                    .map(|_| rng.gen_range(b'a'..b'e'))
+                    .collect::<Vec<u8>>()
+            })
+            .collect::<Vec<Vec<u8>>>();
+        let mut expect = strs.clone();
+        expect.sort();
+        msd_radix_sort(&mut strs);
+        assert_eq!(strs, expect);
+    }
+}
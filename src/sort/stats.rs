@@ -0,0 +1,234 @@
+use std::cmp::Ordering;
+
+use super::quicksort::quick_sort;
+use crate::data_structure::tree_array::TreeArray;
+
+// 归并排序的同时统计逆序对，nums 排序后原样返回计数
+fn _count_inversions<T, F>(nums: &mut [T], buf: &mut Vec<T>, cmp: &mut F) -> u64
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = nums.len();
+    if len < 2 {
+        return 0;
+    }
+    let mid = len / 2;
+    let mut res = _count_inversions(&mut nums[..mid], buf, cmp)
+        + _count_inversions(&mut nums[mid..], buf, cmp);
+
+    buf.clear();
+    buf.extend_from_slice(&nums[..mid]);
+    let (mut i, mut j, mut k) = (0, mid, 0);
+    while i < mid && j < len {
+        if cmp(&nums[j], &buf[i]) == Ordering::Less {
+            // 左边剩下的元素都比 nums[j] 大
+            res += (mid - i) as u64;
+            nums.swap(k, j);
+            j += 1;
+        } else {
+            std::mem::swap(&mut nums[k], &mut buf[i]);
+            i += 1;
+        }
+        k += 1;
+    }
+    while i < mid {
+        std::mem::swap(&mut nums[k], &mut buf[i]);
+        i += 1;
+        k += 1;
+    }
+    res
+}
+
+// 满足 i < j 且 nums[i] > nums[j] 的下标对数
+pub fn count_inversions_by<T, F>(nums: &[T], mut cmp: F) -> u64
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut nums = nums.to_vec();
+    _count_inversions(&mut nums, &mut vec![], &mut cmp)
+}
+
+pub fn count_inversions<T: Ord + Clone>(nums: &[T]) -> u64 {
+    count_inversions_by(nums, T::cmp)
+}
+
+// 离散化：返回每个元素的排名（从 0 开始，相等的元素排名相同）和去重后的有序值
+pub fn coordinate_compress<T: Ord + Clone>(nums: &[T]) -> (Vec<usize>, Vec<T>) {
+    let mut values = nums.to_vec();
+    quick_sort(&mut values);
+    values.dedup();
+    let ranks = nums
+        .iter()
+        .map(|x| values.binary_search(x).unwrap())
+        .collect();
+    (ranks, values)
+}
+
+// ranks 中的值都小于 n
+fn fenwick_inversions(ranks: &[usize], n: usize) -> u64 {
+    let mut tree = TreeArray::new(&vec![0u64; n]);
+    let mut res = 0;
+    for (i, &r) in ranks.iter().enumerate() {
+        // 前面出现过的、不大于当前元素的个数
+        let not_greater = tree.query(r).unwrap();
+        res += i as u64 - not_greater;
+        tree.add(r, 1);
+    }
+    res
+}
+
+// 用树状数组统计逆序对，结果与 count_inversions 相同
+pub fn count_inversions_fenwick<T: Ord + Clone>(nums: &[T]) -> u64 {
+    let (ranks, values) = coordinate_compress(nums);
+    fenwick_inversions(&ranks, values.len())
+}
+
+// a 和 b 是同一组互不相同元素的两种排列，返回两者相对顺序不同的元素对数
+pub fn kendall_tau_distance<T: Ord + Clone>(a: &[T], b: &[T]) -> Option<u64> {
+    let n = a.len();
+    if b.len() != n {
+        return None;
+    }
+    let (ranks_b, values) = coordinate_compress(b);
+    if values.len() != n {
+        return None;
+    }
+    let mut pos_in_b = vec![0; n];
+    for (i, &r) in ranks_b.iter().enumerate() {
+        pos_in_b[r] = i;
+    }
+
+    let mut seen = vec![false; n];
+    let mut seq = Vec::with_capacity(n);
+    for x in a {
+        let r = values.binary_search(x).ok()?;
+        if seen[r] {
+            return None;
+        }
+        seen[r] = true;
+        seq.push(pos_in_b[r]);
+    }
+    Some(fenwick_inversions(&seq, n))
+}
+
+// Kendall tau 相关系数，取值 [-1, 1]，1 表示顺序完全相同
+pub fn kendall_tau<T: Ord + Clone>(a: &[T], b: &[T]) -> Option<f64> {
+    let n = a.len() as f64;
+    let d = kendall_tau_distance(a, b)? as f64;
+    if a.len() < 2 {
+        return Some(1.0);
+    }
+    Some(1.0 - 4.0 * d / (n * (n - 1.0)))
+}
+
+// 返回一个最长严格递增子序列的下标
+pub fn longest_increasing_subsequence_by<T, F>(nums: &[T], mut cmp: F) -> Vec<usize>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // tails[l] 为长度 l + 1 的递增子序列中结尾最小的那个的下标
+    let mut tails: Vec<usize> = vec![];
+    let mut prev: Vec<Option<usize>> = vec![None; nums.len()];
+    for i in 0..nums.len() {
+        let pos = tails.partition_point(|&t| cmp(&nums[t], &nums[i]) == Ordering::Less);
+        if pos > 0 {
+            prev[i] = Some(tails[pos - 1]);
+        }
+        if pos == tails.len() {
+            tails.push(i);
+        } else {
+            tails[pos] = i;
+        }
+    }
+
+    let mut res = vec![];
+    let mut cur = tails.last().copied();
+    while let Some(i) = cur {
+        res.push(i);
+        cur = prev[i];
+    }
+    res.reverse();
+    res
+}
+
+pub fn longest_increasing_subsequence<T: Ord>(nums: &[T]) -> Vec<usize> {
+    longest_increasing_subsequence_by(nums, T::cmp)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{seq::SliceRandom, thread_rng, Rng};
+
+    fn brute_inversions(nums: &[i32]) -> u64 {
+        let mut res = 0;
+        for i in 0..nums.len() {
+            for j in i + 1..nums.len() {
+                if nums[i] > nums[j] {
+                    res += 1;
+                }
+            }
+        }
+        res
+    }
+
+    #[test]
+    fn test_inversions() {
+        assert_eq!(count_inversions::<i32>(&[]), 0);
+        assert_eq!(count_inversions(&[1, 2, 3]), 0);
+        assert_eq!(count_inversions(&[3, 2, 1]), 3);
+        assert_eq!(count_inversions(&[2, 2, 1]), 2);
+
+        let mut rng = thread_rng();
+        for n in [1, 10, 100, 500] {
+            let nums = (0..n).map(|_| rng.gen_range(0..20)).collect::<Vec<i32>>();
+            let expect = brute_inversions(&nums);
+            assert_eq!(count_inversions(&nums), expect);
+            assert_eq!(count_inversions_fenwick(&nums), expect);
+        }
+    }
+
+    #[test]
+    fn test_compress() {
+        let (ranks, values) = coordinate_compress(&[100, -5, 100, 7]);
+        assert_eq!(ranks, [2, 0, 2, 1]);
+        assert_eq!(values, [-5, 7, 100]);
+    }
+
+    #[test]
+    fn test_kendall_tau() {
+        let a = ["a", "b", "c", "d"];
+        assert_eq!(kendall_tau_distance(&a, &a), Some(0));
+        assert_eq!(kendall_tau_distance(&a, &["d", "c", "b", "a"]), Some(6));
+        assert_eq!(kendall_tau_distance(&a, &["b", "a", "c", "d"]), Some(1));
+        assert_eq!(kendall_tau(&a, &["d", "c", "b", "a"]), Some(-1.0));
+        assert_eq!(kendall_tau_distance(&a, &["a", "b", "c"]), None);
+        assert_eq!(kendall_tau_distance(&a, &["a", "b", "c", "c"]), None);
+        assert_eq!(kendall_tau_distance(&a, &["a", "b", "c", "e"]), None);
+
+        let mut rng = thread_rng();
+        let mut p = (0..200).collect::<Vec<i32>>();
+        p.shuffle(&mut rng);
+        let id = (0..200).collect::<Vec<i32>>();
+        assert_eq!(kendall_tau_distance(&p, &id), Some(brute_inversions(&p)));
+    }
+
+    #[test]
+    fn test_lis() {
+        let nums = [3, 1, 4, 1, 5, 9, 2, 6];
+        let lis = longest_increasing_subsequence(&nums);
+        assert_eq!(lis.len(), 4);
+        assert!(lis
+            .windows(2)
+            .all(|x| x[0] < x[1] && nums[x[0]] < nums[x[1]]));
+
+        assert!(longest_increasing_subsequence::<i32>(&[]).is_empty());
+        assert_eq!(longest_increasing_subsequence(&[2, 2, 2]).len(), 1);
+        assert_eq!(
+            longest_increasing_subsequence_by(&[1, 5, 2, 4, 3], |a: &i32, b: &i32| b.cmp(a)),
+            [1, 3, 4]
+        );
+    }
+}
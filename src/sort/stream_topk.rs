@@ -0,0 +1,219 @@
+use crate::data_structure::priority_queue::{Compare, PriorityQueue};
+
+struct Entry<K, T> {
+    key: K,
+    // 插入顺序，键相同时先出现的元素优先保留
+    seq: usize,
+    item: T,
+}
+
+// 堆顶是键最小的元素，键相同时后出现的先被淘汰
+struct EntryCompare;
+
+impl<K: Ord, T> Compare<Entry<K, T>> for EntryCompare {
+    fn before(&self, a: &Entry<K, T>, b: &Entry<K, T>) -> bool {
+        a.key < b.key || (a.key == b.key && a.seq > b.seq)
+    }
+}
+
+// 流式 top-k：用大小为 k 的小根堆保存当前最大的 k 个元素
+pub struct StreamTopK<T, K, F> {
+    k: usize,
+    key: F,
+    heap: PriorityQueue<Entry<K, T>, EntryCompare>,
+    seq: usize,
+    keep_ties: bool,
+    // 与第 k 大元素键相同、但被挤出堆的元素
+    ties: Vec<Entry<K, T>>,
+}
+
+impl<T, K, F> StreamTopK<T, K, F>
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    fn build(k: usize, key: F, keep_ties: bool) -> Self {
+        StreamTopK {
+            k,
+            key,
+            heap: PriorityQueue::with_compare(None, EntryCompare),
+            seq: 0,
+            keep_ties,
+            ties: vec![],
+        }
+    }
+
+    pub fn new(k: usize, key: F) -> Self {
+        Self::build(k, key, false)
+    }
+
+    // 结果中额外保留所有与第 k 大元素并列的元素
+    pub fn with_ties(k: usize, key: F) -> Self {
+        Self::build(k, key, true)
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len() + self.ties.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn push_entry(&mut self, entry: Entry<K, T>) {
+        if self.k == 0 {
+            return;
+        }
+        self.heap.push(entry);
+        if self.heap.len() <= self.k {
+            return;
+        }
+        let evicted = self.heap.pop().unwrap();
+        if !self.keep_ties {
+            return;
+        }
+        let threshold = &self.heap.peek().unwrap().key;
+        if evicted.key == *threshold {
+            self.ties.push(evicted);
+        } else if self.ties.first().is_some_and(|x| x.key != *threshold) {
+            self.ties.clear();
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        let entry = Entry {
+            key: (self.key)(&item),
+            seq: self.seq,
+            item,
+        };
+        self.seq += 1;
+        self.push_entry(entry);
+    }
+
+    pub fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+
+    // 合并另一个分片的结果，other 中的元素视为出现在当前分片之后
+    pub fn merge<G>(&mut self, mut other: StreamTopK<T, K, G>) {
+        let offset = self.seq;
+        while let Some(mut entry) = other.heap.pop() {
+            entry.seq += offset;
+            self.push_entry(entry);
+        }
+        for mut entry in other.ties {
+            entry.seq += offset;
+            self.push_entry(entry);
+        }
+        self.seq += other.seq;
+    }
+
+    // 按键从大到小返回，键相同时按出现顺序
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut res = self
+            .heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|x| x.item)
+            .collect::<Vec<T>>();
+        res.reverse();
+        self.ties.sort_by_key(|x| x.seq);
+        res.extend(self.ties.into_iter().map(|x| x.item));
+        res
+    }
+}
+
+pub fn topk_iter_by_key<I, T, K, F>(iter: I, k: usize, key: F) -> Vec<T>
+where
+    I: IntoIterator<Item = T>,
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    let mut topk = StreamTopK::new(k, key);
+    topk.extend(iter);
+    topk.into_sorted_vec()
+}
+
+pub fn topk_iter<I, T>(iter: I, k: usize) -> Vec<T>
+where
+    I: IntoIterator<Item = T>,
+    T: Ord + Clone,
+{
+    topk_iter_by_key(iter, k, |x| x.clone())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn test1() {
+        assert_eq!(topk_iter(vec![3, 1, 4, 1, 5, 9, 2, 6], 3), [9, 6, 5]);
+        assert_eq!(topk_iter(vec![3, 1], 5), [3, 1]);
+        assert!(topk_iter(Vec::<i32>::new(), 3).is_empty());
+        assert!(topk_iter(vec![1, 2, 3], 0).is_empty());
+
+        // 元素可以借用局部数据
+        let text = String::from("pear apple fig banana cherry");
+        let words = text.split(' ').collect::<Vec<&str>>();
+        assert_eq!(topk_iter(words.iter(), 2), [&"pear", &"fig"]);
+        assert_eq!(topk_iter_by_key(words, 1, |x| x.len()), ["banana"]);
+    }
+
+    #[test]
+    fn test_key_and_ties() {
+        let words = vec!["aa", "b", "cc", "ddd", "ee", "f"];
+        // 长度相同时先出现的优先
+        assert_eq!(
+            topk_iter_by_key(words.clone(), 2, |x| x.len()),
+            ["ddd", "aa"]
+        );
+
+        let mut topk = StreamTopK::with_ties(2, |x: &&str| x.len());
+        topk.extend(words);
+        assert_eq!(topk.into_sorted_vec(), ["ddd", "aa", "cc", "ee"]);
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = thread_rng();
+        let nums = (0..10000)
+            .map(|_| rng.gen_range(0..100000))
+            .collect::<Vec<i32>>();
+        let mut expect = nums.clone();
+        expect.sort_by(|a, b| b.cmp(a));
+        expect.truncate(50);
+        assert_eq!(topk_iter(nums.into_iter(), 50), expect);
+    }
+
+    #[test]
+    fn test_merge() {
+        let nums = (0..1000).map(|x| (x * 37) % 1000).collect::<Vec<i32>>();
+        let mut shards = nums.chunks(100).map(|chunk| {
+            let mut topk = StreamTopK::new(10, |x: &i32| *x);
+            topk.extend(chunk.iter().copied());
+            topk
+        });
+        let mut res = shards.next().unwrap();
+        for shard in shards {
+            res.merge(shard);
+        }
+        assert_eq!(
+            res.into_sorted_vec(),
+            (990..1000).rev().collect::<Vec<i32>>()
+        );
+    }
+
+    #[test]
+    fn test_merge_ties() {
+        let mut a = StreamTopK::with_ties(1, |x: &(i32, char)| x.0);
+        a.extend([(5, 'a'), (1, 'b')]);
+        let mut b = StreamTopK::with_ties(1, |x: &(i32, char)| x.0);
+        b.extend([(5, 'c'), (5, 'd')]);
+        a.merge(b);
+        assert_eq!(a.into_sorted_vec(), [(5, 'a'), (5, 'c'), (5, 'd')]);
+    }
+}
@@ -0,0 +1,444 @@
+use std::cmp::Ordering;
+
+const MIN_MERGE: usize = 64;
+const MIN_GALLOP: usize = 7;
+
+fn min_run_length(mut n: usize) -> usize {
+    let mut r = 0;
+    while n >= MIN_MERGE {
+        r |= n & 1;
+        n >>= 1;
+    }
+    n + r
+}
+
+// 统计从头开始的 run 长度，严格递减的 run 会被翻转
+fn count_run<T, F>(nums: &mut [T], cmp: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = nums.len();
+    if len < 2 {
+        return len;
+    }
+    let mut end = 2;
+    if cmp(&nums[1], &nums[0]) == Ordering::Less {
+        while end < len && cmp(&nums[end], &nums[end - 1]) == Ordering::Less {
+            end += 1;
+        }
+        nums[..end].reverse();
+    } else {
+        while end < len && cmp(&nums[end], &nums[end - 1]) != Ordering::Less {
+            end += 1;
+        }
+    }
+    end
+}
+
+// nums[..start] 已经有序
+fn binary_insertion_sort<T, F>(nums: &mut [T], start: usize, cmp: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in start.max(1)..nums.len() {
+        let (sorted, rest) = nums.split_at(i);
+        let pos = sorted.partition_point(|x| cmp(&rest[0], x) != Ordering::Less);
+        nums[pos..=i].rotate_right(1);
+    }
+}
+
+// run 中 <= key 的元素个数
+fn gallop_right<T, F>(key: &T, run: &[T], cmp: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut hi = 1;
+    while hi <= run.len() && cmp(key, &run[hi - 1]) != Ordering::Less {
+        hi *= 2;
+    }
+    let lo = hi / 2;
+    let hi = hi.min(run.len() + 1) - 1;
+    lo + run[lo..hi].partition_point(|x| cmp(key, x) != Ordering::Less)
+}
+
+// run 中 < key 的元素个数
+fn gallop_left<T, F>(key: &T, run: &[T], cmp: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut hi = 1;
+    while hi <= run.len() && cmp(&run[hi - 1], key) == Ordering::Less {
+        hi *= 2;
+    }
+    let lo = hi / 2;
+    let hi = hi.min(run.len() + 1) - 1;
+    lo + run[lo..hi].partition_point(|x| cmp(x, key) == Ordering::Less)
+}
+
+struct TimSort<'a, T, F> {
+    nums: &'a mut [T],
+    cmp: F,
+    buf: Vec<T>,
+    runs: Vec<(usize, usize)>,
+    min_gallop: usize,
+}
+
+impl<'a, T, F> TimSort<'a, T, F>
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // 左边的 run 较短，拷贝到 buf 后从前往后合并
+    fn merge_lo(&mut self, lo: usize, mid: usize, hi: usize) {
+        let nums = &mut self.nums[lo..hi];
+        let cmp = &mut self.cmp;
+        let buf = &mut self.buf;
+        buf.clear();
+        buf.extend_from_slice(&nums[..mid - lo]);
+
+        let len1 = buf.len();
+        let len = nums.len();
+        let (mut i, mut j, mut k) = (0, len1, 0);
+        'outer: while i < len1 && j < len {
+            let mut count1 = 0;
+            let mut count2 = 0;
+            loop {
+                if cmp(&nums[j], &buf[i]) == Ordering::Less {
+                    nums.swap(k, j);
+                    k += 1;
+                    j += 1;
+                    count2 += 1;
+                    count1 = 0;
+                    if j == len {
+                        break 'outer;
+                    }
+                } else {
+                    std::mem::swap(&mut nums[k], &mut buf[i]);
+                    k += 1;
+                    i += 1;
+                    count1 += 1;
+                    count2 = 0;
+                    if i == len1 {
+                        break 'outer;
+                    }
+                }
+                if count1 >= self.min_gallop || count2 >= self.min_gallop {
+                    break;
+                }
+            }
+
+            // galloping 模式
+            loop {
+                let c1 = gallop_right(&nums[j], &buf[i..], cmp);
+                for _ in 0..c1 {
+                    std::mem::swap(&mut nums[k], &mut buf[i]);
+                    k += 1;
+                    i += 1;
+                }
+                if i == len1 {
+                    break 'outer;
+                }
+                nums.swap(k, j);
+                k += 1;
+                j += 1;
+                if j == len {
+                    break 'outer;
+                }
+
+                let c2 = gallop_left(&buf[i], &nums[j..], cmp);
+                for _ in 0..c2 {
+                    nums.swap(k, j);
+                    k += 1;
+                    j += 1;
+                }
+                if j == len {
+                    break 'outer;
+                }
+                std::mem::swap(&mut nums[k], &mut buf[i]);
+                k += 1;
+                i += 1;
+                if i == len1 {
+                    break 'outer;
+                }
+
+                if self.min_gallop > 1 {
+                    self.min_gallop -= 1;
+                }
+                if c1 < MIN_GALLOP && c2 < MIN_GALLOP {
+                    break;
+                }
+            }
+            self.min_gallop += 2;
+        }
+
+        while i < len1 {
+            std::mem::swap(&mut nums[k], &mut buf[i]);
+            k += 1;
+            i += 1;
+        }
+    }
+
+    // 右边的 run 较短，拷贝到 buf 后从后往前合并
+    fn merge_hi(&mut self, lo: usize, mid: usize, hi: usize) {
+        let nums = &mut self.nums[lo..hi];
+        let cmp = &mut self.cmp;
+        let buf = &mut self.buf;
+        buf.clear();
+        buf.extend_from_slice(&nums[mid - lo..]);
+
+        let (mut i, mut j, mut k) = (mid - lo, buf.len(), nums.len());
+        'outer: while i > 0 && j > 0 {
+            let mut count1 = 0;
+            let mut count2 = 0;
+            loop {
+                if cmp(&buf[j - 1], &nums[i - 1]) == Ordering::Less {
+                    nums.swap(k - 1, i - 1);
+                    k -= 1;
+                    i -= 1;
+                    count1 += 1;
+                    count2 = 0;
+                    if i == 0 {
+                        break 'outer;
+                    }
+                } else {
+                    std::mem::swap(&mut nums[k - 1], &mut buf[j - 1]);
+                    k -= 1;
+                    j -= 1;
+                    count2 += 1;
+                    count1 = 0;
+                    if j == 0 {
+                        break 'outer;
+                    }
+                }
+                if count1 >= self.min_gallop || count2 >= self.min_gallop {
+                    break;
+                }
+            }
+
+            // galloping 模式
+            loop {
+                let c1 = i - gallop_right(&buf[j - 1], &nums[..i], cmp);
+                for _ in 0..c1 {
+                    nums.swap(k - 1, i - 1);
+                    k -= 1;
+                    i -= 1;
+                }
+                if i == 0 {
+                    break 'outer;
+                }
+                std::mem::swap(&mut nums[k - 1], &mut buf[j - 1]);
+                k -= 1;
+                j -= 1;
+                if j == 0 {
+                    break 'outer;
+                }
+
+                let c2 = j - gallop_left(&nums[i - 1], &buf[..j], cmp);
+                for _ in 0..c2 {
+                    std::mem::swap(&mut nums[k - 1], &mut buf[j - 1]);
+                    k -= 1;
+                    j -= 1;
+                }
+                if j == 0 {
+                    break 'outer;
+                }
+                nums.swap(k - 1, i - 1);
+                k -= 1;
+                i -= 1;
+                if i == 0 {
+                    break 'outer;
+                }
+
+                if self.min_gallop > 1 {
+                    self.min_gallop -= 1;
+                }
+                if c1 < MIN_GALLOP && c2 < MIN_GALLOP {
+                    break;
+                }
+            }
+            self.min_gallop += 2;
+        }
+
+        while j > 0 {
+            std::mem::swap(&mut nums[k - 1], &mut buf[j - 1]);
+            k -= 1;
+            j -= 1;
+        }
+    }
+
+    // 合并 runs[n] 和 runs[n + 1]
+    fn merge_at(&mut self, n: usize) {
+        let (start1, len1) = self.runs[n];
+        let (start2, len2) = self.runs[n + 1];
+        self.runs[n] = (start1, len1 + len2);
+        self.runs.remove(n + 1);
+
+        // 左边 run 中 <= 右边首元素的部分已经就位
+        let skip = gallop_right(
+            &self.nums[start2],
+            &self.nums[start1..start2],
+            &mut self.cmp,
+        );
+        let lo = start1 + skip;
+        if lo == start2 {
+            return;
+        }
+        // 右边 run 中 >= 左边末元素的部分已经就位
+        let hi = start2
+            + gallop_left(
+                &self.nums[start2 - 1],
+                &self.nums[start2..start2 + len2],
+                &mut self.cmp,
+            );
+        if hi == start2 {
+            return;
+        }
+
+        if start2 - lo <= hi - start2 {
+            self.merge_lo(lo, start2, hi);
+        } else {
+            self.merge_hi(lo, start2, hi);
+        }
+    }
+
+    fn merge_collapse(&mut self) {
+        while self.runs.len() > 1 {
+            let mut n = self.runs.len() - 2;
+            let len = |i: usize, runs: &Vec<(usize, usize)>| runs[i].1;
+            if (n > 0 && len(n - 1, &self.runs) <= len(n, &self.runs) + len(n + 1, &self.runs))
+                || (n > 1 && len(n - 2, &self.runs) <= len(n - 1, &self.runs) + len(n, &self.runs))
+            {
+                if len(n - 1, &self.runs) < len(n + 1, &self.runs) {
+                    n -= 1;
+                }
+            } else if len(n, &self.runs) > len(n + 1, &self.runs) {
+                break;
+            }
+            self.merge_at(n);
+        }
+    }
+
+    fn merge_force_collapse(&mut self) {
+        while self.runs.len() > 1 {
+            let mut n = self.runs.len() - 2;
+            if n > 0 && self.runs[n - 1].1 < self.runs[n + 1].1 {
+                n -= 1;
+            }
+            self.merge_at(n);
+        }
+    }
+
+    fn sort(&mut self) {
+        let len = self.nums.len();
+        let min_run = min_run_length(len);
+        let mut start = 0;
+        while start < len {
+            let mut run_len = count_run(&mut self.nums[start..], &mut self.cmp);
+            if run_len < min_run {
+                let force = min_run.min(len - start);
+                binary_insertion_sort(&mut self.nums[start..start + force], run_len, &mut self.cmp);
+                run_len = force;
+            }
+            self.runs.push((start, run_len));
+            self.merge_collapse();
+            start += run_len;
+        }
+        self.merge_force_collapse();
+    }
+}
+
+pub fn tim_sort_by<T, F>(nums: &mut [T], cmp: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if nums.len() < 2 {
+        return;
+    }
+    TimSort {
+        nums,
+        cmp,
+        buf: vec![],
+        runs: vec![],
+        min_gallop: MIN_GALLOP,
+    }
+    .sort();
+}
+
+pub fn tim_sort_by_key<T, K, F>(nums: &mut [T], mut f: F)
+where
+    T: Clone,
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    tim_sort_by(nums, |a, b| f(a).cmp(&f(b)));
+}
+
+pub fn tim_sort<T: Ord + Clone>(nums: &mut [T]) {
+    tim_sort_by(nums, |a, b| a.cmp(b));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn test1() {
+        let mut nums = vec![3, 4, 1, 5, 2];
+        tim_sort(&mut nums);
+        assert_eq!(nums, [1, 2, 3, 4, 5]);
+
+        let mut nums: Vec<i32> = vec![];
+        tim_sort(&mut nums);
+        assert!(nums.is_empty());
+    }
+
+    #[test]
+    fn test_gallop() {
+        let run = [1, 2, 2, 2, 3, 5, 8];
+        let mut cmp = |a: &i32, b: &i32| a.cmp(b);
+        assert_eq!(gallop_right(&2, &run, &mut cmp), 4);
+        assert_eq!(gallop_left(&2, &run, &mut cmp), 1);
+        assert_eq!(gallop_right(&0, &run, &mut cmp), 0);
+        assert_eq!(gallop_left(&9, &run, &mut cmp), 7);
+    }
+
+    #[test]
+    fn test_patterns() {
+        let mut rng = thread_rng();
+        let n = 50000;
+        let random = (0..n)
+            .map(|_| rng.gen_range(-1000..1000))
+            .collect::<Vec<i32>>();
+        let sorted = (0..n).collect::<Vec<i32>>();
+        let reversed = (0..n).rev().collect::<Vec<i32>>();
+        let saw = (0..n).map(|x| x % 777).collect::<Vec<i32>>();
+        // 交错的有序块，触发 galloping
+        let blocks = (0..n)
+            .map(|x| if (x / 500) % 2 == 0 { x } else { n - x })
+            .collect::<Vec<i32>>();
+
+        for mut nums in [random, sorted, reversed, saw, blocks] {
+            let mut expect = nums.clone();
+            expect.sort();
+            tim_sort(&mut nums);
+            assert_eq!(nums, expect);
+        }
+    }
+
+    #[test]
+    fn test_stable() {
+        let mut rng = thread_rng();
+        let mut nums = (0..20000)
+            .map(|i| (rng.gen_range(0..50), i))
+            .collect::<Vec<(i32, i32)>>();
+        // 部分有序的输入
+        nums[..10000].sort_by_key(|x| x.0);
+        let mut expect = nums.clone();
+        expect.sort_by_key(|x| x.0);
+
+        tim_sort_by_key(&mut nums, |x| x.0);
+        assert_eq!(nums, expect);
+    }
+}
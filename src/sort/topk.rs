@@ -1,49 +1,115 @@
-pub fn partition<T>(nums: &mut Vec<T>, start: usize, end: usize) -> usize
+use std::cmp::Ordering;
+
+use super::network::network_sort_slice_by;
+use super::quicksort::{choose_pivot, depth_limit, partition, quick_sort_by, SMALL_SORT_THRESHOLD};
+
+// 五个一组取中位数，再递归选出中位数的中位数，返回其下标
+fn median_of_medians<T, F>(nums: &mut [T], cmp: &mut F) -> usize
 where
-    T: PartialOrd,
+    F: FnMut(&T, &T) -> Ordering,
 {
-    let mut i = start;
+    let groups = nums.len() / 5;
+    for g in 0..groups {
+        let group = &mut nums[g * 5..g * 5 + 5];
+        network_sort_slice_by(group, cmp);
+        nums.swap(g, g * 5 + 2);
+    }
+    let mid = groups / 2;
+    _select(&mut nums[..groups], mid, cmp, 0);
+    mid
+}
 
-    for j in start..end {
-        if &nums[j] > &nums[end] {
-            nums.swap(j, i);
-            i += 1;
+// introselect：先用快速选择，递归过深时改用中位数的中位数选取枢轴
+fn _select<T, F>(mut nums: &mut [T], mut k: usize, cmp: &mut F, mut limit: usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    loop {
+        if nums.len() <= SMALL_SORT_THRESHOLD {
+            network_sort_slice_by(nums, cmp);
+            return;
+        }
+        let pivot = if limit == 0 {
+            median_of_medians(nums, cmp)
+        } else {
+            limit -= 1;
+            choose_pivot(nums, cmp)
+        };
+        let (lt, gt) = partition(nums, pivot, cmp);
+        if k < lt {
+            nums = &mut std::mem::take(&mut nums)[..lt];
+        } else if k >= gt {
+            nums = &mut std::mem::take(&mut nums)[gt..];
+            k -= gt;
+        } else {
+            return;
         }
     }
-    nums.swap(i, end);
-    i
 }
 
-pub fn _topk<T>(nums: &mut Vec<T>, k: usize, start: usize, end: usize)
+// 重排 nums 使 nums[k] 为排序后第 k 个元素，且左边不大于它、右边不小于它
+pub fn select_nth_by<T, F>(nums: &mut [T], k: usize, mut cmp: F) -> Option<&mut T>
 where
-    T: PartialOrd,
+    F: FnMut(&T, &T) -> Ordering,
 {
-    if start >= end {
-        return;
+    if k >= nums.len() {
+        return None;
     }
-    let t = partition(nums, start, end);
-    if t > k - 1 {
-        _topk(nums, k, start, t - 1);
-    } else if t < k - 1 {
-        _topk(nums, k, t + 1, end);
-    } else {
-        return;
+    _select(nums, k, &mut cmp, depth_limit(nums.len()));
+    Some(&mut nums[k])
+}
+
+pub fn select_nth<T: Ord>(nums: &mut [T], k: usize) -> Option<&mut T> {
+    select_nth_by(nums, k, |a, b| a.cmp(b))
+}
+
+pub fn select_nth_desc<T: Ord>(nums: &mut [T], k: usize) -> Option<&mut T> {
+    select_nth_by(nums, k, |a, b| b.cmp(a))
+}
+
+// 将前 k 个元素按 cmp 排好序放在 nums 开头并返回
+pub fn partial_sort_by<T, F>(nums: &mut [T], k: usize, mut cmp: F) -> &mut [T]
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let k = k.min(nums.len());
+    if k == 0 {
+        return &mut nums[..0];
     }
+    select_nth_by(nums, k - 1, &mut cmp);
+    quick_sort_by(&mut nums[..k - 1], &mut cmp);
+    &mut nums[..k]
+}
+
+pub fn partial_sort<T: Ord>(nums: &mut [T], k: usize) -> &mut [T] {
+    partial_sort_by(nums, k, |a, b| a.cmp(b))
+}
+
+pub fn partial_sort_desc<T: Ord>(nums: &mut [T], k: usize) -> &mut [T] {
+    partial_sort_by(nums, k, |a, b| b.cmp(a))
 }
 
+// 最大的 k 个元素，不保证顺序
 pub fn topk<T>(mut nums: Vec<T>, k: usize) -> Vec<T>
 where
     T: PartialOrd,
 {
-    let l = nums.len();
-    _topk(&mut nums, k, 0, l - 1);
-
-    nums.into_iter().take(k).collect()
+    let k = k.min(nums.len());
+    if k == 0 {
+        return vec![];
+    }
+    select_nth_by(&mut nums, k - 1, |a, b| {
+        b.partial_cmp(a).unwrap_or(Ordering::Equal)
+    });
+    nums.truncate(k);
+    nums
 }
 
 #[cfg(test)]
 mod test {
-    use super::topk;
+    use super::*;
+    use rand::{thread_rng, Rng};
+
     #[test]
     fn test1() {
         let nums = vec![1, 2, 3, 4, 5];
@@ -57,4 +123,53 @@ mod test {
         res.sort();
         assert_eq!(res, [3, 4, 5]);
     }
+
+    #[test]
+    fn test_empty() {
+        assert!(topk(Vec::<i32>::new(), 3).is_empty());
+        assert!(topk(vec![1, 2], 0).is_empty());
+        assert_eq!(topk(vec![2, 1], 10).len(), 2);
+
+        let mut nums: Vec<i32> = vec![];
+        assert_eq!(select_nth(&mut nums, 0), None);
+        assert!(partial_sort(&mut nums, 3).is_empty());
+    }
+
+    #[test]
+    fn test_select_nth() {
+        let mut rng = thread_rng();
+        let nums = (0..5000)
+            .map(|_| rng.gen_range(0..300))
+            .collect::<Vec<i32>>();
+        let mut sorted = nums.clone();
+        sorted.sort();
+
+        for k in [0, 1, 17, 2500, 4998, 4999] {
+            let mut a = nums.clone();
+            assert_eq!(select_nth(&mut a, k).copied(), Some(sorted[k]));
+            assert!(a[..k].iter().all(|x| *x <= sorted[k]));
+            assert!(a[k + 1..].iter().all(|x| *x >= sorted[k]));
+
+            let mut b = nums.clone();
+            assert_eq!(select_nth_desc(&mut b, k).copied(), Some(sorted[4999 - k]));
+        }
+    }
+
+    #[test]
+    fn test_median_of_medians() {
+        let mut nums = (0..1000).rev().collect::<Vec<i32>>();
+        _select(&mut nums, 123, &mut |a: &i32, b: &i32| a.cmp(b), 0);
+        assert_eq!(nums[123], 123);
+    }
+
+    #[test]
+    fn test_partial_sort() {
+        let mut nums = vec![5, 1, 9, 3, 7, 2, 8];
+        assert_eq!(partial_sort(&mut nums, 3), [1, 2, 3]);
+        assert_eq!(partial_sort_desc(&mut nums, 2), [9, 8]);
+
+        let mut words = vec!["ccc", "a", "dddd", "bb"];
+        let top = partial_sort_by(&mut words, 2, |a, b| b.len().cmp(&a.len()));
+        assert_eq!(top, ["dddd", "ccc"]);
+    }
 }
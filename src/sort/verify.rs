@@ -0,0 +1,212 @@
+use rand::{thread_rng, Rng};
+
+use super::heapsort::heapsort_by;
+use super::mergesort::{merge_sort_bottom_up_by, merge_sort_by};
+use super::parallel::ParallelSorter;
+use super::quicksort::quick_sort_by;
+use super::radix::{counting_sort_by_key, radix_sort_by_key};
+use super::timsort::tim_sort_by;
+
+// (键, 原始下标)，只按键排序，用下标检查稳定性
+pub type Record = (u32, u32);
+
+#[derive(Clone, Copy, Debug)]
+pub enum Pattern {
+    Sorted,
+    Reversed,
+    Sawtooth,
+    Duplicates,
+    Random,
+}
+
+impl Pattern {
+    pub const ALL: [Pattern; 5] = [
+        Pattern::Sorted,
+        Pattern::Reversed,
+        Pattern::Sawtooth,
+        Pattern::Duplicates,
+        Pattern::Random,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Pattern::Sorted => "sorted",
+            Pattern::Reversed => "reversed",
+            Pattern::Sawtooth => "sawtooth",
+            Pattern::Duplicates => "duplicates",
+            Pattern::Random => "random",
+        }
+    }
+
+    pub fn generate(&self, n: usize) -> Vec<Record> {
+        let mut rng = thread_rng();
+        let n32 = n as u32;
+        let tooth = (n32 / 16).max(1);
+        (0..n32)
+            .map(|i| {
+                let key = match self {
+                    Pattern::Sorted => i,
+                    Pattern::Reversed => n32 - i,
+                    Pattern::Sawtooth => i % tooth,
+                    Pattern::Duplicates => rng.gen_range(0..8),
+                    Pattern::Random => rng.gen_range(0..n32.saturating_mul(4).max(1)),
+                };
+                (key, i)
+            })
+            .collect()
+    }
+}
+
+pub struct Algorithm {
+    pub name: &'static str,
+    pub stable: bool,
+    pub sort: fn(&mut [Record]),
+}
+
+pub fn algorithms() -> Vec<Algorithm> {
+    fn cmp(a: &Record, b: &Record) -> std::cmp::Ordering {
+        a.0.cmp(&b.0)
+    }
+    vec![
+        Algorithm {
+            name: "quick_sort",
+            stable: false,
+            sort: |x| quick_sort_by(x, cmp),
+        },
+        Algorithm {
+            name: "heapsort",
+            stable: false,
+            sort: |x| heapsort_by(x, cmp),
+        },
+        Algorithm {
+            name: "merge_sort",
+            stable: true,
+            sort: |x| merge_sort_by(x, cmp),
+        },
+        Algorithm {
+            name: "merge_sort_bottom_up",
+            stable: true,
+            sort: |x| merge_sort_bottom_up_by(x, cmp),
+        },
+        Algorithm {
+            name: "tim_sort",
+            stable: true,
+            sort: |x| tim_sort_by(x, cmp),
+        },
+        Algorithm {
+            name: "radix_sort",
+            stable: true,
+            sort: |x| radix_sort_by_key(x, |r| r.0),
+        },
+        Algorithm {
+            name: "counting_sort",
+            stable: true,
+            sort: |x| counting_sort_by_key(x, |r| r.0 as i64),
+        },
+        Algorithm {
+            name: "par_merge_sort",
+            stable: true,
+            sort: |x| ParallelSorter::default().merge_sort_by(x, cmp),
+        },
+        Algorithm {
+            name: "par_quick_sort",
+            stable: false,
+            sort: |x| ParallelSorter::default().quick_sort_by(x, cmp),
+        },
+        Algorithm {
+            name: "std::sort",
+            stable: true,
+            sort: |x| x.sort_by(cmp),
+        },
+        Algorithm {
+            name: "std::sort_unstable",
+            stable: false,
+            sort: |x| x.sort_unstable_by(cmp),
+        },
+    ]
+}
+
+pub fn is_sorted(nums: &[Record]) -> bool {
+    nums.windows(2).all(|x| x[0].0 <= x[1].0)
+}
+
+// 下标互不相同，比较排序前后的下标集合和对应的键即可
+pub fn is_permutation(input: &[Record], output: &[Record]) -> bool {
+    if input.len() != output.len() {
+        return false;
+    }
+    let mut seen = vec![false; input.len()];
+    output.iter().all(|&(key, idx)| {
+        let idx = idx as usize;
+        let ok = idx < input.len() && !seen[idx] && input[idx].0 == key;
+        if ok {
+            seen[idx] = true;
+        }
+        ok
+    })
+}
+
+pub fn is_stable(nums: &[Record]) -> bool {
+    nums.windows(2).all(|x| x[0].0 != x[1].0 || x[0].1 < x[1].1)
+}
+
+// 排序后检查结果，input 中的下标需为 0..n
+pub fn check(alg: &Algorithm, input: &[Record]) -> Result<Vec<Record>, String> {
+    let mut output = input.to_vec();
+    (alg.sort)(&mut output);
+    if !is_permutation(input, &output) {
+        return Err(format!(
+            "{}: output is not a permutation of input",
+            alg.name
+        ));
+    }
+    if !is_sorted(&output) {
+        return Err(format!("{}: output is not sorted", alg.name));
+    }
+    if alg.stable && !is_stable(&output) {
+        return Err(format!("{}: output is not stable", alg.name));
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_checkers() {
+        let input = vec![(2, 0), (1, 1), (2, 2)];
+        assert!(is_permutation(&input, &[(1, 1), (2, 0), (2, 2)]));
+        assert!(!is_permutation(&input, &[(1, 1), (2, 0), (2, 0)]));
+        assert!(!is_permutation(&input, &[(1, 1), (2, 0)]));
+        assert!(is_stable(&[(1, 1), (2, 0), (2, 2)]));
+        assert!(!is_stable(&[(1, 1), (2, 2), (2, 0)]));
+        assert!(!is_sorted(&[(2, 0), (1, 1)]));
+    }
+
+    #[test]
+    fn test_all_algorithms() {
+        for alg in algorithms() {
+            for pattern in Pattern::ALL {
+                for n in [0, 1, 2, 5, 16, 17, 33, 100, 1000, 20000] {
+                    let input = pattern.generate(n);
+                    if let Err(e) = check(&alg, &input) {
+                        panic!("{e} ({}, n = {n})", pattern.name());
+                    }
+                }
+            }
+        }
+    }
+
+    // 稳定的算法之间结果应当完全一致
+    #[test]
+    fn test_stable_agree() {
+        let input = Pattern::Duplicates.generate(5000);
+        let results = algorithms()
+            .iter()
+            .filter(|alg| alg.stable)
+            .map(|alg| check(alg, &input).unwrap())
+            .collect::<Vec<Vec<Record>>>();
+        assert!(results.windows(2).all(|x| x[0] == x[1]));
+    }
+}